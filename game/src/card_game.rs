@@ -1,14 +1,17 @@
 use bevy::{
-    app::{App, Plugin},
+    app::{App, Plugin, Update},
     ecs::system::Resource,
     state::{app::AppExtStates, state::States},
 };
 
-use card_game_logic::game_logic::local::LocalGameLogic;
+use card_game_logic::{bot::SeatType, game_logic, game_logic::rules::RuleSet};
 use game_logic_runner::game_logic_facade::GameLogicFacade;
+use preferences::Preferences;
 
+pub mod config;
 pub mod game_logic_runner;
 pub mod game_ui;
+pub mod preferences;
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 pub enum GameState {
@@ -20,12 +23,63 @@ pub enum GameState {
     OnlineGame,
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct GameSettings {
     pub player_count: usize,
     pub inital_card_count: usize,
     pub online_player_name: String,
     pub online_room_name: String,
+    pub seat_types: Vec<SeatType>,
+    // Empty names fall back to "Player N"
+    pub player_names: Vec<String>,
+    // A fixed seed replays the same deals and bot choices
+    pub seed: Option<u64>,
+    pub bot_think_delay: f32,
+    // Scales how fast cards move, 2.0 plays every animation twice as fast
+    pub animation_speed: f32,
+    // Hides every hand until the next local player confirms they have the device
+    pub hot_seat_handoff: bool,
+    pub rules: RuleSet,
+}
+
+impl GameSettings {
+    pub fn seat_type(&self, player_id: usize) -> SeatType {
+        self.seat_types
+            .get(player_id)
+            .copied()
+            .unwrap_or(SeatType::Human)
+    }
+
+    pub fn player_name(&self, player_id: usize) -> String {
+        self.player_names
+            .get(player_id)
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("Player {}", player_id + 1))
+    }
+
+    pub fn human_count(&self) -> usize {
+        (0..self.player_count)
+            .filter(|player_id| self.seat_type(*player_id) == SeatType::Human)
+            .count()
+    }
+
+    pub fn logic_settings(&self) -> game_logic::GameSettings {
+        game_logic::GameSettings {
+            player_count: self.player_count,
+            initial_card_count: self.inital_card_count,
+            rules: self.rules.clone(),
+        }
+    }
+
+    pub fn set_seat_type(&mut self, player_id: usize, seat_type: SeatType) {
+        if self.seat_types.len() <= player_id {
+            self.seat_types.resize(player_id + 1, SeatType::Human);
+        }
+
+        self.seat_types[player_id] = seat_type;
+    }
 }
 
 impl Default for GameSettings {
@@ -35,6 +89,13 @@ impl Default for GameSettings {
             inital_card_count: 3,
             online_player_name: "Player".to_string(),
             online_room_name: "Room".to_string(),
+            seat_types: Vec::new(),
+            player_names: Vec::new(),
+            seed: None,
+            bot_think_delay: 1.0,
+            animation_speed: 1.0,
+            hot_seat_handoff: true,
+            rules: RuleSet::default(),
         }
     }
 }
@@ -49,6 +110,8 @@ impl Plugin for CardGamePlugin {
         app.init_state::<GameState>()
             .insert_resource(GameSettings::default())
             .insert_resource(GameLogicRes(GameLogicFacade::new()))
+            .init_resource::<Preferences>()
+            .add_systems(Update, preferences::apply_preferences)
             .add_plugins(game_logic_runner::GameLogicRunnerPlugin)
             .add_plugins(game_ui::GameUIPlugin);
     }
@@ -0,0 +1,58 @@
+use std::{env, fs, path::PathBuf};
+
+const APP_DIR: &str = "guessing_card_game";
+
+// The per-user config directory of each platform, e.g. ~/.config/guessing_card_game on Linux
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    base.map(|base| base.join(APP_DIR))
+}
+
+// None when the file was never written
+pub fn read_config(file_name: &str) -> Result<Option<String>, String> {
+    let path = config_dir()
+        .ok_or("No config directory")?
+        .join(file_name);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))
+}
+
+pub fn write_config(file_name: &str, contents: &str) -> Result<(), String> {
+    let dir = config_dir().ok_or("No config directory")?;
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Could not create {}: {}", dir.display(), err))?;
+
+    let path = dir.join(file_name);
+    fs::write(&path, contents).map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
+
+// Config files hold one "key = value" pair per line, lines starting with # are comments
+pub fn parse_entries(text: &str) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Line {} is not a \"key = value\" pair", index + 1))?;
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
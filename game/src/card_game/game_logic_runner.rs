@@ -1,10 +1,15 @@
 pub mod components;
 pub mod events;
 pub mod game_logic_facade;
+pub mod resources;
 pub mod systems;
 
 use bevy::prelude::*;
-use events::{CardPlayed, GameEnded, PlayerGuessed, PlayerInfoUpdated};
+use events::{
+    CardPlayed, GameEnded, GameLogicEvent, PauseRequested, PlayerEliminated, PlayerGuessed,
+    RedoRequested, ResumeRequested, SkipTrickRequested, UndoRequested,
+};
+use resources::LastGameOutcome;
 
 use super::GameState;
 
@@ -24,10 +29,17 @@ pub struct GameLogicRunnerPlugin;
 impl Plugin for GameLogicRunnerPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<MatchState>()
+            .init_resource::<LastGameOutcome>()
             .add_event::<GameEnded>()
             .add_event::<CardPlayed>()
             .add_event::<PlayerGuessed>()
-            .add_event::<PlayerInfoUpdated>()
+            .add_event::<GameLogicEvent>()
+            .add_event::<PlayerEliminated>()
+            .add_event::<UndoRequested>()
+            .add_event::<RedoRequested>()
+            .add_event::<PauseRequested>()
+            .add_event::<ResumeRequested>()
+            .add_event::<SkipTrickRequested>()
             .add_systems(
                 OnEnter(GameState::LocalGameInit),
                 (
@@ -48,6 +60,21 @@ impl Plugin for GameLogicRunnerPlugin {
                 )
                     .chain(),
             )
+            .add_systems(
+                Update,
+                systems::handle_pause_requests
+                    .run_if(in_state(GameState::LocalGame).or(in_state(GameState::OnlineGame))),
+            )
+            .add_systems(
+                Update,
+                systems::sync_bot_think_delay.run_if(in_state(GameState::LocalGame)),
+            )
+            .add_systems(OnExit(GameState::LocalGame), systems::teardown_match)
+            .add_systems(OnExit(GameState::OnlineGame), systems::teardown_match)
+            .add_systems(
+                Update,
+                systems::poll_online_updates.run_if(in_state(GameState::OnlineGame)),
+            )
             .add_systems(
                 Update,
                 (systems::clear_cards).run_if(in_state(MatchState::DisplayingPlayedCard)),
@@ -56,14 +83,33 @@ impl Plugin for GameLogicRunnerPlugin {
                 OnExit(MatchState::DisplayingPlayedCard),
                 systems::spawn_cards,
             )
+            .add_systems(
+                Update,
+                systems::drive_bot_turns
+                    .before(systems::handle_player_guess)
+                    .before(systems::handle_card_played)
+                    .run_if(in_state(GameState::LocalGame))
+                    .run_if(in_state(MatchState::Guessing).or(in_state(MatchState::Playing))),
+            )
             .add_systems(
                 Update,
                 (
                     systems::handle_player_guess,
                     systems::handle_card_played,
                     systems::update_player_infos,
+                    systems::update_max_guess,
+                    systems::record_game_outcome,
                 )
                     .run_if(in_state(GameState::LocalGame)),
+            )
+            .add_systems(
+                Update,
+                systems::handle_history_requests
+                    .before(systems::handle_player_guess)
+                    .before(systems::handle_card_played)
+                    .before(systems::update_player_infos)
+                    .run_if(in_state(GameState::LocalGame))
+                    .run_if(in_state(MatchState::Guessing).or(in_state(MatchState::Playing))),
             );
     }
 }
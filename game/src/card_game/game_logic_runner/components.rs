@@ -12,6 +12,7 @@ pub struct CurrentPlayer(pub usize);
 pub struct PlayerInfo{
     pub player_id: usize,
     pub card_count: usize,
+    pub lives: usize,
     pub guess: usize,
     pub wins: usize,
 }
@@ -1,10 +1,18 @@
 use bevy::ecs::event::Event;
 
-use card_game_logic::game_logic::common::Card;
+use card_game_logic::game_logic::{
+    common::{Card, GameOutcome},
+    events::GameEvent,
+};
 
 #[derive(Event)]
 pub struct GameEnded {
-    pub winner: usize,
+    pub outcome: GameOutcome,
+}
+
+#[derive(Event)]
+pub struct PlayerEliminated {
+    pub player_id: usize,
 }
 
 #[derive(Event)]
@@ -19,5 +27,24 @@ pub struct CardPlayed {
     pub card: Card,
 }
 
+// Forwards what changed in the game logic after each action
+#[derive(Event)]
+pub struct GameLogicEvent {
+    pub event: GameEvent,
+}
+
+#[derive(Event)]
+pub struct UndoRequested;
+
+#[derive(Event)]
+pub struct RedoRequested;
+
+#[derive(Event)]
+pub struct PauseRequested;
+
+#[derive(Event)]
+pub struct ResumeRequested;
+
+// Clears the finished trick without waiting for it to be shown
 #[derive(Event)]
-pub struct PlayerInfoUpdated;
+pub struct SkipTrickRequested;
@@ -1,5 +1,11 @@
 use card_game_logic::game_logic::{
-    GameLogic, common::PlayedCard, local::LocalGameLogic, online::OnlineGameLogic,
+    GameLogic, SettingsError,
+    common::{Card, GameOutcome, PlayedCard},
+    events::GameEvent,
+    history::{MatchRecord, TrickRecord},
+    local::LocalGameLogic,
+    online::OnlineGameLogic,
+    rules::RuleSet,
 };
 
 pub struct GameLogicFacade {
@@ -15,9 +21,17 @@ impl GameLogicFacade {
         }
     }
 
-    pub fn init_local(&mut self, player_count: usize) -> &GameLogicFacade {
+    pub fn init_local(
+        &mut self,
+        player_count: usize,
+        rules: RuleSet,
+        seed: Option<u64>,
+    ) -> &GameLogicFacade {
         let mut game_logic = LocalGameLogic::default();
-        game_logic.init(player_count);
+        if let Some(seed) = seed {
+            game_logic.set_seed(seed);
+        }
+        game_logic.init(player_count, rules);
         self.local_game_logic = Some(game_logic);
         self.online_game_logic = None;
 
@@ -35,10 +49,47 @@ impl GameLogicFacade {
         }
 
         self.local_game_logic = None;
-        self.online_game_logic = Some(OnlineGameLogic::new());
-        
+        self.online_game_logic = Some(game_logic);
+
         self
     }
+
+    // Applies the state updates the server sent since the last frame
+    pub fn poll_updates(&mut self) -> Result<(), String> {
+        match self.online_game_logic {
+            Some(ref mut game_logic) => game_logic.process_messages(),
+            None => Ok(()),
+        }
+    }
+}
+
+// Taking moves back is only possible when the whole game runs locally
+impl GameLogicFacade {
+    pub fn can_undo(&self) -> bool {
+        self.local_game_logic
+            .as_ref()
+            .is_some_and(|game_logic| game_logic.can_undo())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.local_game_logic
+            .as_ref()
+            .is_some_and(|game_logic| game_logic.can_redo())
+    }
+
+    pub fn undo(&mut self) -> Result<(), String> {
+        match self.local_game_logic {
+            Some(ref mut game_logic) => game_logic.undo(),
+            None => Err(String::from("Undo is only available in local games")),
+        }
+    }
+
+    pub fn redo(&mut self) -> Result<(), String> {
+        match self.local_game_logic {
+            Some(ref mut game_logic) => game_logic.redo(),
+            None => Err(String::from("Redo is only available in local games")),
+        }
+    }
 }
 
 fn panic_not_initialized() -> ! {
@@ -49,23 +100,29 @@ impl GameLogic for GameLogicFacade {
     fn start_match(
         &mut self,
         inital_card_count: usize,
-    ) -> card_game_logic::game_logic::common::CardPlayedResult {
-        match self.local_game_logic {
-            Some(ref mut game_logic) => {
+    ) -> Result<card_game_logic::game_logic::common::CardPlayedResult, SettingsError> {
+        match (&mut self.local_game_logic, &mut self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.start_match(inital_card_count);
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.start_match(inital_card_count);
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn set_guess(&mut self, player_id: usize, guess: usize) -> Result<(), String> {
-        match self.local_game_logic {
-            Some(ref mut game_logic) => {
+        match (&mut self.local_game_logic, &mut self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.set_guess(player_id, guess);
+            }
+            (None, Some(game_logic)) => {
                 return game_logic.set_guess(player_id, guess);
             }
-            None => (),
+            _ => (),
         }
 
         panic_not_initialized();
@@ -76,11 +133,14 @@ impl GameLogic for GameLogicFacade {
         player_id: usize,
         card: &card_game_logic::game_logic::common::Card,
     ) -> Result<card_game_logic::game_logic::common::CardPlayedResult, String> {
-        match self.local_game_logic {
-            Some(ref mut game_logic) => {
+        match (&mut self.local_game_logic, &mut self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.play_card(player_id, card);
+            }
+            (None, Some(game_logic)) => {
                 return game_logic.play_card(player_id, card);
             }
-            None => (),
+            _ => (),
         }
 
         panic_not_initialized();
@@ -90,99 +150,154 @@ impl GameLogic for GameLogicFacade {
         &self,
         player_id: usize,
     ) -> &Vec<card_game_logic::game_logic::common::Card> {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_player_cards(player_id);
+            }
+            (None, Some(game_logic)) => {
                 return game_logic.get_player_cards(player_id);
             }
-            None => (),
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_player_card_count(&self, player_id: usize) -> usize {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_player_card_count(player_id);
+            }
+            (None, Some(game_logic)) => {
                 return game_logic.get_player_card_count(player_id);
             }
-            None => (),
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_hand_size(&self, player_id: usize) -> usize {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_hand_size(player_id);
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_hand_size(player_id);
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_player_turn(&self) -> usize {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.get_player_turn();
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_player_turn();
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_player_guess(&self, player_id: usize) -> usize {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.get_player_guess(player_id);
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_player_guess(player_id);
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn has_guessed(&self, player_id: usize) -> bool {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.has_guessed(player_id);
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.has_guessed(player_id);
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_player_wins(&self, player_id: usize) -> usize {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.get_player_wins(player_id);
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_player_wins(player_id);
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
-    fn get_winner(&self) -> usize {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
-                return game_logic.get_winner();
+    fn get_outcome(&self) -> Option<GameOutcome> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_outcome();
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_outcome();
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_game_over(&self) -> bool {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.get_game_over();
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_game_over();
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_played_cards(&self) -> &Vec<PlayedCard> {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
                 return game_logic.get_played_cards();
             }
-            None => (),
+            (None, Some(game_logic)) => {
+                return game_logic.get_played_cards();
+            }
+            _ => (),
         }
 
         panic_not_initialized()
     }
 
     fn get_guessing_round(&self) -> bool {
-        match self.local_game_logic {
-            Some(ref game_logic) => {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_guessing_round();
+            }
+            (None, Some(game_logic)) => {
                 return game_logic.get_guessing_round();
             }
-            None => (),
+            _ => (),
         }
 
         panic_not_initialized()
@@ -192,7 +307,7 @@ impl GameLogic for GameLogicFacade {
         match (&self.local_game_logic, &self.online_game_logic) {
             (Some(game_logic), None) => {
                 return game_logic.get_player_count();
-            },
+            }
             (None, Some(game_logic)) => {
                 return game_logic.get_player_count();
             }
@@ -201,4 +316,144 @@ impl GameLogic for GameLogicFacade {
 
         panic_not_initialized()
     }
+
+    fn get_forbidden_guess(&self) -> Option<usize> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_forbidden_guess();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_forbidden_guess();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_rules(&self) -> &RuleSet {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_rules();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_rules();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_vira(&self) -> Option<Card> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_vira();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_vira();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn is_hand_visible_to(&self, owner_id: usize, viewer_id: usize) -> bool {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.is_hand_visible_to(owner_id, viewer_id);
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.is_hand_visible_to(owner_id, viewer_id);
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_player_lives(&self, player_id: usize) -> usize {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_player_lives(player_id);
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_player_lives(player_id);
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_round_number(&self) -> usize {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_round_number();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_round_number();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_total_rounds(&self) -> Option<usize> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_total_rounds();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_total_rounds();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        match (&mut self.local_game_logic, &mut self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.take_events();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.take_events();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_trick_history(&self) -> &Vec<TrickRecord> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_trick_history();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_trick_history();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
+
+    fn get_match_history(&self) -> &Vec<MatchRecord> {
+        match (&self.local_game_logic, &self.online_game_logic) {
+            (Some(game_logic), None) => {
+                return game_logic.get_match_history();
+            }
+            (None, Some(game_logic)) => {
+                return game_logic.get_match_history();
+            }
+            _ => (),
+        }
+
+        panic_not_initialized()
+    }
 }
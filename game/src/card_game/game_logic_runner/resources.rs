@@ -0,0 +1,18 @@
+use bevy::{ecs::system::Resource, time::Timer};
+
+use card_game_logic::{bot::Bot, game_logic::common::GameOutcome};
+
+use super::MatchState;
+
+#[derive(Resource)]
+pub struct LocalBots(pub Vec<Option<Box<dyn Bot + Send + Sync>>>);
+
+#[derive(Resource)]
+pub struct BotThinkTimer(pub Timer);
+
+#[derive(Resource, Default)]
+pub struct LastGameOutcome(pub Option<GameOutcome>);
+
+// The state the match goes back to when it is resumed
+#[derive(Resource)]
+pub struct PausedMatchState(pub MatchState);
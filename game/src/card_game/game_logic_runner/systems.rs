@@ -1,31 +1,73 @@
+use std::time::Duration;
+
 use bevy::{
     ecs::{
+        change_detection::DetectChanges,
         entity::Entity,
         event::{EventReader, EventWriter},
-        query::With,
+        query::{Or, With},
         system::{Commands, Query, Res, ResMut, Single},
     },
     hierarchy::DespawnRecursiveExt,
-    state::state::NextState,
+    log::warn,
+    state::state::{NextState, State},
     time::{Time, Timer, TimerMode},
 };
 
 use crate::card_game::{GameLogicRes, GameSettings, GameState};
 
-use card_game_logic::game_logic::{
-    GameLogic,
-    common::{Card as CardStruct, CardPlayedResult},
+use card_game_logic::{
+    bot::{SeatType, create_bot, create_seeded_bot},
+    game_logic::{
+        GameLogic,
+        common::{Card as CardStruct, CardPlayedResult, GameOutcome},
+        events::GameEvent,
+    },
 };
 
 use super::{
     MatchState,
     components::{self, CurrentPlayer, DisplayPlayedCardTimer, MaxGuess, TopPlayedCard},
-    events::{CardPlayed, GameEnded, PlayerGuessed, PlayerInfoUpdated},
+    events::{
+        CardPlayed, GameEnded, GameLogicEvent, PauseRequested, PlayerEliminated, PlayerGuessed,
+        RedoRequested, ResumeRequested, SkipTrickRequested, UndoRequested,
+    },
     game_logic_facade::GameLogicFacade,
+    resources::{BotThinkTimer, LastGameOutcome, LocalBots, PausedMatchState},
 };
 
-pub fn local_game_init(mut game_logic: ResMut<GameLogicRes>, game_settings: Res<GameSettings>) {
-    game_logic.0.init_local(game_settings.inital_card_count);
+// How long a finished trick stays on the table at normal animation speed
+pub const TRICK_DISPLAY_SECONDS: f32 = 2.5;
+
+pub fn local_game_init(
+    mut commands: Commands,
+    mut game_logic: ResMut<GameLogicRes>,
+    game_settings: Res<GameSettings>,
+) {
+    game_logic.0.init_local(
+        game_settings.player_count,
+        game_settings.rules.clone(),
+        game_settings.seed,
+    );
+
+    // Every bot gets its own seed so they do not all make the same random choices
+    let bots = (0..game_logic.0.get_player_count())
+        .map(|player_id| match game_settings.seat_type(player_id) {
+            SeatType::Human => None,
+            SeatType::Bot(difficulty) => Some(match game_settings.seed {
+                Some(seed) => {
+                    create_seeded_bot(difficulty, seed.wrapping_add(player_id as u64 + 1))
+                }
+                None => create_bot(difficulty),
+            }),
+        })
+        .collect();
+
+    commands.insert_resource(LocalBots(bots));
+    commands.insert_resource(BotThinkTimer(Timer::from_seconds(
+        game_settings.bot_think_delay,
+        TimerMode::Once,
+    )));
 }
 
 pub fn online_game_init(mut game_logic: ResMut<GameLogicRes>, game_settings: Res<GameSettings>) {
@@ -35,16 +77,105 @@ pub fn online_game_init(mut game_logic: ResMut<GameLogicRes>, game_settings: Res
     );
 }
 
+// Leaves nothing from the finished match behind, so a new one starts from a clean slate
+pub fn teardown_match(
+    mut commands: Commands,
+    mut game_logic: ResMut<GameLogicRes>,
+    mut match_state: ResMut<NextState<MatchState>>,
+    match_entities: Query<
+        Entity,
+        Or<(
+            With<components::Card>,
+            With<components::PlayerInfo>,
+            With<CurrentPlayer>,
+            With<MaxGuess>,
+            With<DisplayPlayedCardTimer>,
+        )>,
+    >,
+) {
+    for entity in match_entities.iter() {
+        commands.entity(entity).try_despawn_recursive();
+    }
+
+    commands.remove_resource::<LocalBots>();
+    commands.remove_resource::<BotThinkTimer>();
+    commands.remove_resource::<PausedMatchState>();
+    game_logic.0 = GameLogicFacade::new();
+    match_state.set(MatchState::Disabled);
+}
+
+pub fn handle_pause_requests(
+    mut commands: Commands,
+    current_match_state: Res<State<MatchState>>,
+    paused_match_state: Option<Res<PausedMatchState>>,
+    mut pause_events: EventReader<PauseRequested>,
+    mut resume_events: EventReader<ResumeRequested>,
+    mut match_state: ResMut<NextState<MatchState>>,
+) {
+    let pause_requested = pause_events.read().count() > 0;
+    let resume_requested = resume_events.read().count() > 0;
+
+    match current_match_state.get() {
+        MatchState::Paused => {
+            if resume_requested {
+                let resumed_state = paused_match_state
+                    .map(|paused| paused.0)
+                    .unwrap_or(MatchState::Guessing);
+                commands.remove_resource::<PausedMatchState>();
+                match_state.set(resumed_state);
+            }
+        }
+        // Leaving the played cards screen early would skip clearing the trick, and a finished
+        // game has its own screen
+        MatchState::DisplayingPlayedCard | MatchState::Finished => (),
+        state => {
+            if pause_requested {
+                commands.insert_resource(PausedMatchState(*state));
+                match_state.set(MatchState::Paused);
+            }
+        }
+    }
+}
+
+// Picks up think delay changes made from the pause menu
+pub fn sync_bot_think_delay(
+    game_settings: Res<GameSettings>,
+    think_timer: Option<ResMut<BotThinkTimer>>,
+) {
+    let Some(mut think_timer) = think_timer else {
+        return;
+    };
+
+    if game_settings.is_changed() {
+        think_timer
+            .0
+            .set_duration(Duration::from_secs_f32(game_settings.bot_think_delay));
+    }
+}
+
+pub fn poll_online_updates(mut game_logic: ResMut<GameLogicRes>) {
+    if let Err(err) = game_logic.0.poll_updates() {
+        warn!("{}", err);
+    }
+}
+
 pub fn handle_game_start(
     mut commands: Commands,
     mut game_logic: ResMut<GameLogicRes>,
     game_settings: Res<GameSettings>,
     mut match_state: ResMut<NextState<MatchState>>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut game_logic_events: EventWriter<GameLogicEvent>,
 ) {
-    game_logic.0.start_match(game_settings.inital_card_count);
+    // The menu already checked the settings, so this only fails if they were changed since
+    if let Err(err) = game_logic.0.start_match(game_settings.inital_card_count) {
+        warn!("Could not start the game: {}", err);
+        game_state.set(GameState::Menu);
+        return;
+    }
+    send_game_logic_events(&mut game_logic, &mut game_logic_events, None);
 
-    commands.spawn(MaxGuess(game_settings.inital_card_count));
+    commands.spawn(MaxGuess(guess_limit(&game_logic)));
 
     commands.spawn(CurrentPlayer(game_logic.0.get_player_turn()));
     game_state.set(GameState::LocalGame);
@@ -57,7 +188,7 @@ pub fn handle_player_guess(
     mut event: EventReader<PlayerGuessed>,
     mut current_player: Single<&mut CurrentPlayer>,
     mut match_state: ResMut<NextState<MatchState>>,
-    mut player_info_event: EventWriter<PlayerInfoUpdated>,
+    mut game_logic_events: EventWriter<GameLogicEvent>,
 ) {
     for event in event.read() {
         match game_logic.0.set_guess(event.player_id, event.guess) {
@@ -70,7 +201,7 @@ pub fn handle_player_guess(
                     match_state.as_mut(),
                 );
 
-                player_info_event.send(PlayerInfoUpdated);
+                send_game_logic_events(&mut game_logic, &mut game_logic_events, None);
             }
             Err(_) => (),
         }
@@ -86,11 +217,19 @@ pub fn handle_card_played(
     mut match_state: ResMut<NextState<MatchState>>,
     mut cards: Query<(Entity, &mut components::Card)>,
     top_card: Option<Single<Entity, With<TopPlayedCard>>>,
-    mut player_info_event: EventWriter<PlayerInfoUpdated>,
+    mut game_logic_events: EventWriter<GameLogicEvent>,
+    mut player_eliminated_writer: EventWriter<PlayerEliminated>,
 ) {
     let mapped_top_card = top_card.map(|t| *t);
     for event in event.read() {
-        match game_logic.0.play_card(event.player_id, &event.card) {
+        let result = game_logic.0.play_card(event.player_id, &event.card);
+        send_game_logic_events(
+            &mut game_logic,
+            &mut game_logic_events,
+            Some(&mut player_eliminated_writer),
+        );
+
+        match result {
             Ok(CardPlayedResult::NextPlayer) => {
                 define_card_as_played(
                     &mut commands,
@@ -108,7 +247,6 @@ pub fn handle_card_played(
                     current_player.as_mut(),
                     match_state.as_mut(),
                 );
-                player_info_event.send(PlayerInfoUpdated);
             }
             Ok(CardPlayedResult::NextTurn) | Ok(CardPlayedResult::NextMatch) => {
                 define_card_as_played(
@@ -127,7 +265,6 @@ pub fn handle_card_played(
                     current_player.as_mut(),
                     match_state.as_mut(),
                 );
-                player_info_event.send(PlayerInfoUpdated);
             }
             Ok(CardPlayedResult::GameOver) => {
                 define_card_as_played(
@@ -138,10 +275,13 @@ pub fn handle_card_played(
                     &mut cards,
                     &mapped_top_card,
                 );
-                player_info_event.send(PlayerInfoUpdated);
 
-                let winner = game_logic.0.get_winner();
-                game_ended_writer.send(GameEnded { winner });
+                let outcome = game_logic
+                    .0
+                    .get_outcome()
+                    .unwrap_or(GameOutcome::Abandoned);
+                game_ended_writer.send(GameEnded { outcome });
+                match_state.set(MatchState::Finished);
             },
             Ok(CardPlayedResult::WaitUpdate) => {
                 todo!()
@@ -153,9 +293,147 @@ pub fn handle_card_played(
     }
 }
 
-pub fn spawn_cards(mut commands: Commands, game_logic: Res<GameLogicRes>) {
-    for player_id in 0..game_logic.0.get_player_count() {
-        let cards = game_logic.0.get_player_cards(player_id as usize);
+fn send_game_logic_events(
+    game_logic: &mut GameLogicRes,
+    game_logic_events: &mut EventWriter<GameLogicEvent>,
+    mut player_eliminated_writer: Option<&mut EventWriter<PlayerEliminated>>,
+) {
+    for event in game_logic.0.take_events() {
+        if let (GameEvent::PlayerEliminated { player_id }, Some(writer)) =
+            (&event, player_eliminated_writer.as_mut())
+        {
+            writer.send(PlayerEliminated {
+                player_id: *player_id,
+            });
+        }
+
+        game_logic_events.send(GameLogicEvent { event });
+    }
+}
+
+pub fn drive_bot_turns(
+    time: Res<Time>,
+    game_logic: Res<GameLogicRes>,
+    mut bots: ResMut<LocalBots>,
+    mut think_timer: ResMut<BotThinkTimer>,
+    mut player_guessed_events: EventWriter<PlayerGuessed>,
+    mut card_played_events: EventWriter<CardPlayed>,
+) {
+    let player_id = game_logic.0.get_player_turn();
+    let Some(Some(bot)) = bots.0.get_mut(player_id) else {
+        think_timer.0.reset();
+        return;
+    };
+
+    if !think_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    think_timer.0.reset();
+
+    let view = game_logic.0.get_player_view(player_id);
+
+    if game_logic.0.get_guessing_round() {
+        let guess = bot.choose_guess(&view);
+        player_guessed_events.send(PlayerGuessed { player_id, guess });
+    } else {
+        let card = bot.choose_card(&view);
+        card_played_events.send(CardPlayed { player_id, card });
+    }
+}
+
+pub fn handle_history_requests(
+    mut commands: Commands,
+    mut game_logic: ResMut<GameLogicRes>,
+    game_settings: Res<GameSettings>,
+    mut undo_events: EventReader<UndoRequested>,
+    mut redo_events: EventReader<RedoRequested>,
+    mut current_player: Single<&mut CurrentPlayer>,
+    mut match_state: ResMut<NextState<MatchState>>,
+    cards: Query<Entity, With<components::Card>>,
+    mut game_logic_events: EventWriter<GameLogicEvent>,
+) {
+    let undo_count = undo_events.read().count();
+    let redo_count = redo_events.read().count();
+    if undo_count == 0 && redo_count == 0 {
+        return;
+    }
+
+    // Bot moves are taken back and replayed together with the human move around them
+    let is_bot_turn = |game_logic: &GameLogicRes| {
+        game_settings.seat_type(game_logic.0.get_player_turn()) != SeatType::Human
+    };
+
+    for _ in 0..undo_count {
+        if game_logic.0.undo().is_err() {
+            break;
+        }
+
+        while is_bot_turn(&game_logic) && game_logic.0.can_undo() {
+            let _ = game_logic.0.undo();
+        }
+    }
+
+    for _ in 0..redo_count {
+        if game_logic.0.redo().is_err() {
+            break;
+        }
+
+        while is_bot_turn(&game_logic) && game_logic.0.can_redo() {
+            let _ = game_logic.0.redo();
+        }
+    }
+
+    send_game_logic_events(&mut game_logic, &mut game_logic_events, None);
+
+    // The restored state can have any card anywhere, so the card entities are rebuilt
+    for card_entity in cards.iter() {
+        commands.entity(card_entity).despawn_recursive();
+    }
+    spawn_hand_cards(&mut commands, &game_logic.0);
+    spawn_played_cards(&mut commands, &game_logic.0);
+
+    // Touching the current player makes the hands get laid out again
+    current_player.0 = game_logic.0.get_player_turn();
+    update_current_player(
+        false,
+        &mut commands,
+        &game_logic,
+        current_player.as_mut(),
+        match_state.as_mut(),
+    );
+}
+
+fn spawn_played_cards(commands: &mut Commands, game_logic: &GameLogicFacade) {
+    let played_cards = game_logic.get_played_cards();
+    for (index, played_card) in played_cards.iter().enumerate() {
+        let mut entity = commands.spawn(components::Card {
+            player_id: None,
+            card: played_card.card,
+        });
+
+        if index == played_cards.len() - 1 && !played_card.cancelled {
+            entity.insert(TopPlayedCard);
+        }
+    }
+}
+
+pub fn spawn_cards(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    cards: Query<&components::Card>,
+) {
+    // Hands in progress keep their cards, only a new match deals fresh ones
+    if cards.iter().any(|card| card.player_id.is_some()) {
+        return;
+    }
+
+    spawn_hand_cards(&mut commands, &game_logic.0);
+}
+
+fn spawn_hand_cards(commands: &mut Commands, game_logic: &GameLogicFacade) {
+    for player_id in 0..game_logic.get_player_count() {
+        let cards = game_logic.get_player_cards(player_id as usize);
         for card in cards.iter() {
             commands.spawn(components::Card {
                 player_id: Some(player_id as usize),
@@ -168,11 +446,15 @@ pub fn spawn_cards(mut commands: Commands, game_logic: Res<GameLogicRes>) {
 pub fn clear_cards(
     mut commands: Commands,
     time: Res<Time>,
+    game_settings: Res<GameSettings>,
     display_played_card_timer: Option<Single<(Entity, &mut DisplayPlayedCardTimer)>>,
-    mut cards: Query<Entity, With<components::Card>>,
+    mut skip_events: EventReader<SkipTrickRequested>,
+    cards: Query<(Entity, &components::Card)>,
     mut match_state: ResMut<NextState<MatchState>>,
     mut current_player: Single<&mut CurrentPlayer>,
 ) {
+    let skipped = skip_events.read().count() > 0;
+
     if display_played_card_timer.is_none() {
         return;
     }
@@ -180,11 +462,15 @@ pub fn clear_cards(
     let timer_entity = display_played_card_timer.as_ref().unwrap().0;
     let timer_component = &mut (display_played_card_timer.unwrap().1);
 
-    timer_component.timer.tick(time.delta());
+    timer_component
+        .timer
+        .tick(time.delta().mul_f32(game_settings.animation_speed));
 
-    if timer_component.timer.finished() {
-        for card_entity in cards.iter_mut() {
-            commands.entity(card_entity).despawn_recursive();
+    if timer_component.timer.finished() || skipped {
+        for (card_entity, card) in cards.iter() {
+            if card.player_id.is_none() {
+                commands.entity(card_entity).despawn_recursive();
+            }
         }
 
         commands.entity(timer_entity).despawn_recursive();
@@ -212,7 +498,7 @@ fn update_current_player(
 
     if clear_played_cards {
         commands.spawn(DisplayPlayedCardTimer {
-            timer: Timer::from_seconds(3.0, TimerMode::Once),
+            timer: Timer::from_seconds(TRICK_DISPLAY_SECONDS, TimerMode::Once),
             match_state: next_state,
             next_player_id: current_player_id,
         });
@@ -234,22 +520,33 @@ fn define_card_as_played(
     cards: &mut Query<(Entity, &mut components::Card)>,
     top_card: &Option<Entity>,
 ) {
+    // Cancelled cards can push the previous top card down, so every card on the table is checked
+    let winning_card = game_logic
+        .get_played_cards()
+        .last()
+        .filter(|played_card| !played_card.cancelled)
+        .map(|played_card| played_card.card);
+
+    // With several decks a hand can hold identical cards, so only one of them is played
+    // and only one copy on the table is marked as the top card
+    let mut card_moved = false;
+    let mut top_card_found = false;
     for (card_entity, mut card) in cards.iter_mut() {
-        if card.player_id != Some(player_id) {
-            continue;
+        if !card_moved && card.player_id == Some(player_id) && card_value == card.card {
+            card.player_id = None;
+            card_moved = true;
         }
 
-        if card_value == card.card {
-            card.player_id = None;
+        if card.player_id.is_some() {
+            continue;
         }
 
-        if let Some(last_card) = game_logic.get_played_cards().last() {
-            if last_card.card == card.card {
-                commands.entity(card_entity).insert(TopPlayedCard);
-                if let Some(top_card_entity) = top_card {
-                    commands.entity(*top_card_entity).remove::<TopPlayedCard>();
-                }
-            }
+        let is_top_card = !top_card_found && Some(card.card) == winning_card;
+        top_card_found |= is_top_card;
+        if is_top_card && *top_card != Some(card_entity) {
+            commands.entity(card_entity).insert(TopPlayedCard);
+        } else if !is_top_card && *top_card == Some(card_entity) {
+            commands.entity(card_entity).remove::<TopPlayedCard>();
         }
     }
 }
@@ -259,10 +556,12 @@ pub fn setup_player_infos(mut commands: Commands, game_logic: Res<GameLogicRes>)
         let card_count = game_logic.0.get_player_cards(player_id).len();
         let guess = game_logic.0.get_player_guess(player_id);
         let wins = game_logic.0.get_player_wins(player_id);
+        let lives = game_logic.0.get_player_lives(player_id);
 
         commands.spawn(components::PlayerInfo {
             player_id,
             card_count,
+            lives,
             guess,
             wins,
         });
@@ -270,26 +569,81 @@ pub fn setup_player_infos(mut commands: Commands, game_logic: Res<GameLogicRes>)
 }
 
 pub fn update_player_infos(
-    mut event: EventReader<PlayerInfoUpdated>,
+    mut game_logic_events: EventReader<GameLogicEvent>,
     game_logic: Res<GameLogicRes>,
     mut player_info_query: Query<&mut components::PlayerInfo>,
 ) {
-    for _ in event.read() {
+    for GameLogicEvent { event } in game_logic_events.read() {
         for mut player_info in player_info_query.iter_mut() {
             let player_id = player_info.player_id;
 
-            let card_count = game_logic.0.get_player_cards(player_id).len();
-            let guess = game_logic.0.get_player_guess(player_id);
-            let wins = game_logic.0.get_player_wins(player_id);
-
-            if player_info.card_count != card_count
-                || player_info.guess != guess
-                || player_info.wins != wins
-            {
-                player_info.card_count = card_count;
-                player_info.guess = guess;
-                player_info.wins = wins;
+            match event {
+                GameEvent::MatchStarted { hand_sizes, .. } => {
+                    player_info.card_count = hand_sizes[player_id];
+                    player_info.guess = 0;
+                    player_info.wins = 0;
+                }
+                GameEvent::GuessMade { player_id: id, guess } if *id == player_id => {
+                    player_info.guess = *guess;
+                }
+                GameEvent::CardPlayed { player_id: id, .. } if *id == player_id => {
+                    player_info.card_count = player_info.card_count.saturating_sub(1);
+                }
+                GameEvent::TrickFinished {
+                    winner: Some(id),
+                    tricks_won,
+                } if *id == player_id => {
+                    player_info.wins += tricks_won;
+                }
+                GameEvent::LivesLost {
+                    player_id: id,
+                    lives_left,
+                    ..
+                } if *id == player_id => {
+                    player_info.lives = *lives_left;
+                }
+                GameEvent::HistoryRestored => {
+                    player_info.card_count = game_logic.0.get_player_cards(player_id).len();
+                    player_info.lives = game_logic.0.get_player_lives(player_id);
+                    player_info.guess = game_logic.0.get_player_guess(player_id);
+                    player_info.wins = game_logic.0.get_player_wins(player_id);
+                }
+                _ => (),
             }
         }
     }
 }
+
+// Hands change size between matches, and between players when hands shrink with the lives
+pub fn update_max_guess(
+    mut game_logic_events: EventReader<GameLogicEvent>,
+    game_logic: Res<GameLogicRes>,
+    mut max_guess: Single<&mut MaxGuess>,
+) {
+    for GameLogicEvent { event } in game_logic_events.read() {
+        if matches!(
+            event,
+            GameEvent::MatchStarted { .. }
+                | GameEvent::TurnChanged { .. }
+                | GameEvent::HistoryRestored
+        ) {
+            max_guess.0 = guess_limit(&game_logic);
+        }
+    }
+}
+
+// Nobody can guess more tricks than the cards they were dealt this match
+fn guess_limit(game_logic: &GameLogicRes) -> usize {
+    game_logic
+        .0
+        .get_player_card_count(game_logic.0.get_player_turn())
+}
+
+pub fn record_game_outcome(
+    mut game_ended_events: EventReader<GameEnded>,
+    mut last_game_outcome: ResMut<LastGameOutcome>,
+) {
+    for event in game_ended_events.read() {
+        last_game_outcome.0 = Some(event.outcome.clone());
+    }
+}
@@ -3,11 +3,13 @@ use bevy::{
     color::Color,
     prelude::Plugin,
 };
+use controls::ControlsPlugin;
 use match_ui::GameUIMatchPlugin;
 use menu::GameUIMenuPlugin;
 use ui_entities::text_input::TextInputPlugin;
 
 pub mod asset_loader;
+pub mod controls;
 pub mod match_ui;
 pub mod menu;
 pub mod ui_entities;
@@ -28,6 +30,7 @@ impl Plugin for GameUIPlugin {
         app.add_plugins(GameUIMenuPlugin)
             .add_plugins(GameUIMatchPlugin)
             .add_plugins(TextInputPlugin)
+            .add_plugins(ControlsPlugin)
             .add_systems(Startup, systems::setup)
             .add_systems(Update, (systems::button_enabled, systems::button_system));
     }
@@ -1,38 +1,62 @@
-use bevy::{asset::AssetServer, sprite::Sprite};
+use bevy::{
+    asset::{AssetServer, Handle},
+    color::Color,
+    image::Image,
+    math::Vec2,
+    sprite::Sprite,
+};
 
 use card_game_logic::game_logic::common::{Card, Rank, Suit};
 
 pub trait AssetLoader {
     fn load_card_sprite(&self, card: &Card) -> Sprite;
+    fn card_back_sprite(&self, size: Vec2) -> Sprite;
+    fn load_deck_sprites(&self, deck: &[Card]) -> Vec<Handle<Image>>;
 }
 
 impl AssetLoader for AssetServer {
     fn load_card_sprite(&self, card: &Card) -> Sprite {
-        let suit: &str = match card.0 {
-            Suit::Hearts => "h",
-            Suit::Diamonds => "d",
-            Suit::Clubs => "c",
-            Suit::Spades => "s",
-        };
-
-        let rank: &str = match card.1 {
-            Rank::Two => "2",
-            Rank::Three => "3",
-            Rank::Four => "4",
-            Rank::Five => "5",
-            Rank::Six => "6",
-            Rank::Seven => "7",
-            Rank::Eight => "8",
-            Rank::Nine => "9",
-            Rank::Ten => "10",
-            Rank::Jack => "j",
-            Rank::Queen => "q",
-            Rank::King => "k",
-            Rank::Ace => "a",
-        };
-
-        let path = format!("sprites/cards/card_b_{}{}.png", suit, rank);
-
-        Sprite::from_image(self.load(path))
+        Sprite::from_image(self.load(card_sprite_path(card)))
     }
-}
\ No newline at end of file
+
+    // There is no card back in the sprite sheet, so a plain coloured card is used
+    fn card_back_sprite(&self, size: Vec2) -> Sprite {
+        Sprite::from_color(Color::srgb(0.55, 0.1, 0.15), size)
+    }
+
+    // Decks can be stripped or shuffled together, so only the distinct cards in play are loaded
+    fn load_deck_sprites(&self, deck: &[Card]) -> Vec<Handle<Image>> {
+        let mut paths: Vec<String> = deck.iter().map(card_sprite_path).collect();
+        paths.sort();
+        paths.dedup();
+
+        paths.into_iter().map(|path| self.load(path)).collect()
+    }
+}
+
+fn card_sprite_path(card: &Card) -> String {
+    let suit: &str = match card.0 {
+        Suit::Hearts => "h",
+        Suit::Diamonds => "d",
+        Suit::Clubs => "c",
+        Suit::Spades => "s",
+    };
+
+    let rank: &str = match card.1 {
+        Rank::Two => "2",
+        Rank::Three => "3",
+        Rank::Four => "4",
+        Rank::Five => "5",
+        Rank::Six => "6",
+        Rank::Seven => "7",
+        Rank::Eight => "8",
+        Rank::Nine => "9",
+        Rank::Ten => "10",
+        Rank::Jack => "j",
+        Rank::Queen => "q",
+        Rank::King => "k",
+        Rank::Ace => "a",
+    };
+
+    format!("sprites/cards/card_b_{}{}.png", suit, rank)
+}
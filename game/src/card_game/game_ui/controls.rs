@@ -0,0 +1,25 @@
+pub mod resources;
+pub mod systems;
+
+use bevy::{prelude::*, ui::UiSystem};
+
+use resources::{ButtonFocus, ControlBindings};
+
+pub const CONTROLS_FILE: &str = "controls.cfg";
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControlBindings>()
+            .init_resource::<ButtonFocus>()
+            .add_systems(Startup, systems::load_control_bindings)
+            // Presses are made right after the mouse is handled, so every Update system sees them
+            .add_systems(
+                PreUpdate,
+                systems::move_button_focus.after(UiSystem::Focus),
+            )
+            .add_systems(Update, systems::highlight_focused_button)
+            .add_systems(Last, systems::release_pressed_button);
+    }
+}
@@ -0,0 +1,320 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        system::{Query, Res, Resource, SystemParam},
+    },
+    input::{
+        ButtonInput,
+        gamepad::{Gamepad, GamepadButton},
+        keyboard::KeyCode,
+    },
+};
+
+use crate::card_game::config::parse_entries;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlAction {
+    PreviousCard,
+    NextCard,
+    GuessUp,
+    GuessDown,
+    // Plays the focused card, confirms the guess or presses the focused button
+    Confirm,
+    NextButton,
+    Pause,
+}
+
+pub const CONTROL_ACTIONS: [ControlAction; 7] = [
+    ControlAction::PreviousCard,
+    ControlAction::NextCard,
+    ControlAction::GuessUp,
+    ControlAction::GuessDown,
+    ControlAction::Confirm,
+    ControlAction::NextButton,
+    ControlAction::Pause,
+];
+
+impl ControlAction {
+    // The name used in the controls file
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            ControlAction::PreviousCard => "previous_card",
+            ControlAction::NextCard => "next_card",
+            ControlAction::GuessUp => "guess_up",
+            ControlAction::GuessDown => "guess_down",
+            ControlAction::Confirm => "confirm",
+            ControlAction::NextButton => "next_button",
+            ControlAction::Pause => "pause",
+        }
+    }
+}
+
+// Keys are written with their KeyCode names, e.g. "ArrowLeft", gamepad buttons with a "Pad"
+// prefix, e.g. "PadSouth"
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Binding {
+    Key(KeyCode),
+    Pad(GamepadButton),
+}
+
+// Only keys that make sense for the game can be bound, which keeps the file readable
+const BINDABLE_KEYS: [KeyCode; 60] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Enter,
+    KeyCode::NumpadEnter,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Escape,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::Comma,
+    KeyCode::Period,
+];
+
+const BINDABLE_PAD_BUTTONS: [GamepadButton; 16] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftTrigger,
+    GamepadButton::LeftTrigger2,
+    GamepadButton::RightTrigger,
+    GamepadButton::RightTrigger2,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+
+impl Binding {
+    pub fn config_name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Pad(button) => format!("Pad{:?}", button),
+        }
+    }
+
+    pub fn from_config_name(name: &str) -> Result<Self, String> {
+        let found = match name.strip_prefix("Pad") {
+            Some(button_name) => BINDABLE_PAD_BUTTONS
+                .iter()
+                .find(|button| format!("{:?}", button) == button_name)
+                .map(|button| Binding::Pad(*button)),
+            None => BINDABLE_KEYS
+                .iter()
+                .find(|key| format!("{:?}", key) == name)
+                .map(|key| Binding::Key(*key)),
+        };
+
+        found.ok_or_else(|| format!("Unknown key or button {}", name))
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct ControlBindings {
+    pub bindings: Vec<(ControlAction, Vec<Binding>)>,
+}
+
+impl Default for ControlBindings {
+    fn default() -> Self {
+        use Binding::{Key, Pad};
+
+        Self {
+            bindings: vec![
+                (
+                    ControlAction::PreviousCard,
+                    vec![Key(KeyCode::ArrowLeft), Pad(GamepadButton::DPadLeft)],
+                ),
+                (
+                    ControlAction::NextCard,
+                    vec![Key(KeyCode::ArrowRight), Pad(GamepadButton::DPadRight)],
+                ),
+                (
+                    ControlAction::GuessUp,
+                    vec![Key(KeyCode::ArrowUp), Pad(GamepadButton::DPadUp)],
+                ),
+                (
+                    ControlAction::GuessDown,
+                    vec![Key(KeyCode::ArrowDown), Pad(GamepadButton::DPadDown)],
+                ),
+                (
+                    ControlAction::Confirm,
+                    vec![
+                        Key(KeyCode::Enter),
+                        Key(KeyCode::NumpadEnter),
+                        Pad(GamepadButton::South),
+                    ],
+                ),
+                (
+                    ControlAction::NextButton,
+                    vec![Key(KeyCode::Tab), Pad(GamepadButton::RightTrigger)],
+                ),
+                (
+                    ControlAction::Pause,
+                    vec![Key(KeyCode::Escape), Pad(GamepadButton::Start)],
+                ),
+            ],
+        }
+    }
+}
+
+impl ControlBindings {
+    pub fn get(&self, action: ControlAction) -> &[Binding] {
+        self.bindings
+            .iter()
+            .find(|(bound_action, _)| *bound_action == action)
+            .map_or(&[], |(_, bindings)| bindings)
+    }
+
+    pub fn rebind(&mut self, action: ControlAction, bindings: Vec<Binding>) {
+        match self
+            .bindings
+            .iter_mut()
+            .find(|(bound_action, _)| *bound_action == action)
+        {
+            Some((_, bound)) => *bound = bindings,
+            None => self.bindings.push((action, bindings)),
+        }
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut text = String::from(
+            "# One action per line, followed by the keys and gamepad buttons bound to it\n",
+        );
+
+        for action in CONTROL_ACTIONS {
+            let names: Vec<String> = self.get(action).iter().map(Binding::config_name).collect();
+            text.push_str(&format!("{} = {}\n", action.config_name(), names.join(", ")));
+        }
+
+        text
+    }
+
+    // Actions missing from the file keep their default bindings
+    pub fn from_config(text: &str) -> Result<Self, String> {
+        let mut control_bindings = Self::default();
+
+        for (key, value) in parse_entries(text)? {
+            let action = CONTROL_ACTIONS
+                .iter()
+                .find(|action| action.config_name() == key)
+                .ok_or_else(|| format!("Unknown action {}", key))?;
+
+            let bindings = value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(Binding::from_config_name)
+                .collect::<Result<Vec<_>, String>>()?;
+
+            control_bindings.rebind(*action, bindings);
+        }
+
+        Ok(control_bindings)
+    }
+}
+
+// Reads actions instead of raw keys, so every system follows the player's bindings
+#[derive(SystemParam)]
+pub struct ControlInput<'w, 's> {
+    bindings: Res<'w, ControlBindings>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, (Entity, &'static Gamepad)>,
+}
+
+impl ControlInput<'_, '_> {
+    pub fn just_pressed(&self, action: ControlAction) -> bool {
+        self.bindings.get(action).iter().any(|binding| match binding {
+            Binding::Key(key) => self.keys.just_pressed(*key),
+            Binding::Pad(button) => self
+                .gamepads
+                .iter()
+                .any(|(_, gamepad)| gamepad.just_pressed(*button)),
+        })
+    }
+
+    pub fn shift_held(&self) -> bool {
+        self.keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    }
+
+    // Number keys always set the guess, on both the main row and the numpad
+    pub fn just_pressed_digit(&self) -> Option<usize> {
+        const DIGITS: [(KeyCode, KeyCode); 10] = [
+            (KeyCode::Digit0, KeyCode::Numpad0),
+            (KeyCode::Digit1, KeyCode::Numpad1),
+            (KeyCode::Digit2, KeyCode::Numpad2),
+            (KeyCode::Digit3, KeyCode::Numpad3),
+            (KeyCode::Digit4, KeyCode::Numpad4),
+            (KeyCode::Digit5, KeyCode::Numpad5),
+            (KeyCode::Digit6, KeyCode::Numpad6),
+            (KeyCode::Digit7, KeyCode::Numpad7),
+            (KeyCode::Digit8, KeyCode::Numpad8),
+            (KeyCode::Digit9, KeyCode::Numpad9),
+        ];
+
+        DIGITS
+            .iter()
+            .position(|(digit, numpad)| self.keys.any_just_pressed([*digit, *numpad]))
+    }
+}
+
+// The button Tab has moved to, and a button pressed from the keyboard that is let go next frame
+#[derive(Resource, Default)]
+pub struct ButtonFocus {
+    pub focused: Option<Entity>,
+    pub pressed: Option<Entity>,
+}
@@ -0,0 +1,179 @@
+use bevy::{
+    color::{Color, palettes::css::YELLOW},
+    ecs::{
+        change_detection::DetectChanges,
+        entity::Entity,
+        query::With,
+        system::{Commands, Query, Res, ResMut, Single},
+    },
+    input::{ButtonInput, mouse::MouseButton},
+    log::warn,
+    render::view::InheritedVisibility,
+    transform::components::GlobalTransform,
+    ui::{ComputedNode, Interaction, Outline, Val, widget::Button},
+};
+
+use crate::card_game::{
+    config::{read_config, write_config},
+    game_ui::{
+        components::ButtonDisabled, match_ui::components::HandoffScreen,
+        ui_entities::text_input::components::TextInputActive,
+    },
+};
+
+use super::CONTROLS_FILE;
+use super::resources::{ButtonFocus, ControlAction, ControlBindings, ControlInput};
+
+// A missing file is written with the defaults so players have something to edit
+pub fn load_control_bindings(mut commands: Commands) {
+    let bindings = match read_config(CONTROLS_FILE) {
+        Ok(Some(text)) => ControlBindings::from_config(&text).unwrap_or_else(|err| {
+            warn!("Invalid {}, using the default controls: {}", CONTROLS_FILE, err);
+            ControlBindings::default()
+        }),
+        Ok(None) => {
+            let bindings = ControlBindings::default();
+            if let Err(err) = write_config(CONTROLS_FILE, &bindings.to_config()) {
+                warn!("{}", err);
+            }
+            bindings
+        }
+        Err(err) => {
+            warn!("{}", err);
+            ControlBindings::default()
+        }
+    };
+
+    commands.insert_resource(bindings);
+}
+
+// Tab walks through the buttons on screen from top to bottom, Shift+Tab walks back
+pub fn move_button_focus(
+    mut commands: Commands,
+    controls: ControlInput,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut button_focus: ResMut<ButtonFocus>,
+    mut button_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ComputedNode,
+            &InheritedVisibility,
+            &mut Interaction,
+            Option<&ButtonDisabled>,
+        ),
+        With<Button>,
+    >,
+    handoff_screen: Option<Single<Entity, With<HandoffScreen>>>,
+    active_text_input_query: Query<Entity, With<TextInputActive>>,
+) {
+    // Using the mouse hands control back to it
+    // Using the mouse hands control back to it, and the handoff screen has its own key while the
+    // buttons behind it must stay out of reach
+    if mouse_buttons.just_pressed(MouseButton::Left) || handoff_screen.is_some() {
+        if button_focus.focused.is_some() {
+            button_focus.focused = None;
+        }
+        if handoff_screen.is_some() {
+            return;
+        }
+    }
+
+    let mut buttons: Vec<(Entity, f32, f32)> = button_query
+        .iter()
+        .filter(|(_, _, node, visibility, _, disabled)| {
+            // Hidden rows are laid out with no size
+            visibility.get() && node.size().x > 0.0 && disabled.is_none()
+        })
+        .map(|(entity, transform, ..)| {
+            let position = transform.translation();
+            (entity, position.y, position.x)
+        })
+        .collect();
+    buttons.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.2.total_cmp(&b.2)));
+
+    // The focused button may have been despawned or disabled since
+    let focused_index = button_focus
+        .focused
+        .and_then(|focused| buttons.iter().position(|(entity, ..)| *entity == focused));
+    if focused_index.is_none() && button_focus.focused.is_some() {
+        button_focus.focused = None;
+    }
+
+    if controls.just_pressed(ControlAction::NextButton) && !buttons.is_empty() {
+        // Leaving a text input for the buttons stops typing in it
+        for entity in active_text_input_query.iter() {
+            commands.entity(entity).remove::<TextInputActive>();
+        }
+
+        let count = buttons.len();
+        let next = match (focused_index, controls.shift_held()) {
+            (None, false) => 0,
+            (None, true) => count - 1,
+            (Some(index), false) => (index + 1) % count,
+            (Some(index), true) => (index + count - 1) % count,
+        };
+        button_focus.focused = Some(buttons[next].0);
+        return;
+    }
+
+    // Enter belongs to the text input being typed in
+    if !controls.just_pressed(ControlAction::Confirm) || !active_text_input_query.is_empty() {
+        return;
+    }
+
+    let Some(focused) = button_focus.focused else {
+        return;
+    };
+
+    // Handled like a click, the button is let go again at the end of the frame
+    if let Ok((.., mut interaction, _)) = button_query.get_mut(focused) {
+        *interaction = Interaction::Pressed;
+        button_focus.pressed = Some(focused);
+    }
+}
+
+pub fn release_pressed_button(
+    mut button_focus: ResMut<ButtonFocus>,
+    mut interaction_query: Query<&mut Interaction, With<Button>>,
+) {
+    // Checked first so the focus is only marked as changed when something was pressed
+    if button_focus.pressed.is_none() {
+        return;
+    }
+    let Some(pressed) = button_focus.pressed.take() else {
+        return;
+    };
+
+    // The press may already have been let go by the mouse
+    if let Ok(mut interaction) = interaction_query.get_mut(pressed)
+        && *interaction == Interaction::Pressed
+    {
+        *interaction = Interaction::None;
+    }
+}
+
+pub fn highlight_focused_button(
+    mut commands: Commands,
+    button_focus: Res<ButtonFocus>,
+    outlined_query: Query<Entity, (With<Outline>, With<Button>)>,
+) {
+    if !button_focus.is_changed() {
+        return;
+    }
+
+    for entity in outlined_query.iter() {
+        if Some(entity) != button_focus.focused {
+            commands.entity(entity).remove::<Outline>();
+        }
+    }
+
+    if let Some(focused) = button_focus.focused {
+        // The button can be despawned by a screen change in the same frame
+        commands.entity(focused).try_insert(Outline {
+            width: Val::Px(3.0),
+            offset: Val::Px(2.0),
+            color: Color::from(YELLOW),
+        });
+    }
+}
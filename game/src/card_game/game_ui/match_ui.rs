@@ -1,26 +1,64 @@
 pub mod components;
 pub mod events;
+pub mod resources;
 pub mod systems;
 
 use bevy::prelude::*;
 
 use crate::card_game::{GameState, game_logic_runner::MatchState};
+use resources::{CardFocus, TableLayout};
 
 use super::systems::despawn_screen;
 
+pub const CARD_WIDTH: f32 = 130.0;
+pub const CARD_HEIGHT: f32 = 202.0;
+
 pub struct GameUIMatchPlugin;
 
 impl Plugin for GameUIMatchPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::LocalGame), systems::match_ui_setup)
-            .add_systems(OnEnter(GameState::OnlineGame), systems::match_ui_setup)
+        app.init_resource::<TableLayout>()
+            .init_resource::<CardFocus>()
+            .add_systems(
+            OnEnter(GameState::LocalGame),
+            (
+                systems::match_ui_setup,
+                systems::preload_deck_sprites,
+                systems::history_ui_setup,
+                systems::panels_ui_setup,
+                systems::reset_revealed_player,
+            ),
+        )
+            .add_systems(
+                OnEnter(GameState::OnlineGame),
+                (systems::match_ui_setup, systems::panels_ui_setup),
+            )
             .add_systems(OnEnter(MatchState::Guessing), systems::guess_ui_setup)
             .add_systems(OnEnter(MatchState::Paused), systems::pause_setup)
+            .add_systems(
+                Update,
+                systems::handle_pause_key
+                    .run_if(in_state(GameState::LocalGame).or(in_state(GameState::OnlineGame))),
+            )
+            .add_systems(
+                Update,
+                systems::handle_pause_action.run_if(in_state(MatchState::Paused)),
+            )
             .add_systems(OnEnter(MatchState::Playing), systems::setup_play_area)
+            .add_systems(
+                Update,
+                (systems::update_table_layout, systems::resize_play_area).chain(),
+            )
+            .add_systems(OnEnter(MatchState::Finished), systems::game_over_setup)
+            .add_systems(
+                Update,
+                systems::handle_game_over_action.run_if(in_state(MatchState::Finished)),
+            )
             .add_systems(
                 Update,
                 (
                     systems::handle_guess_action,
+                    systems::handle_guess_keys.before(systems::handle_handoff_confirm),
                     systems::enable_disable_add_guess_button,
                     systems::enable_disable_remove_guess_button,
                     systems::handle_guess_changed,
@@ -29,16 +67,31 @@ impl Plugin for GameUIMatchPlugin {
                 )
                     .run_if(in_state(MatchState::Guessing)),
             )
+            .add_systems(
+                Update,
+                (systems::handle_current_player_changed, systems::display_seats)
+                    .chain()
+                    .run_if(in_state(GameState::LocalGame)),
+            )
             .add_systems(
                 Update,
                 (
-                    systems::handle_current_player_changed,
-                    systems::player_info_ui_setup,
-                    systems::player_info_ui_update,
+                    systems::display_played_cards,
+                    systems::display_vira,
+                    systems::dim_cancelled_cards,
+                    systems::update_round_info,
+                    systems::announce_eliminations,
+                    systems::clear_elimination_notices,
+                    systems::enable_disable_history_buttons,
                 )
-                    .chain()
                     .run_if(in_state(GameState::LocalGame)),
             )
+            .add_systems(
+                Update,
+                (systems::handle_history_action, systems::handle_handoff_confirm)
+                    .run_if(in_state(GameState::LocalGame))
+                    .run_if(in_state(MatchState::Guessing).or(in_state(MatchState::Playing))),
+            )
             .add_systems(
                 Update,
                 (
@@ -52,10 +105,45 @@ impl Plugin for GameUIMatchPlugin {
                     .chain()
                     .run_if(in_state(MatchState::Playing)),
             )
+            .add_systems(
+                Update,
+                (systems::handle_card_keys, systems::highlight_focused_card)
+                    .chain()
+                    .before(systems::handle_handoff_confirm)
+                    .run_if(in_state(MatchState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::toggle_panels,
+                    systems::scroll_panels,
+                    systems::update_panels,
+                )
+                    .run_if(in_state(GameState::LocalGame).or(in_state(GameState::OnlineGame))),
+            )
+            .add_systems(
+                Update,
+                (systems::animate_card_tweens, systems::animate_card_flips)
+                    .run_if(in_state(GameState::LocalGame).or(in_state(GameState::OnlineGame)))
+                    .run_if(not(in_state(MatchState::Paused))),
+            )
+            .add_systems(
+                Update,
+                (systems::collect_trick, systems::request_trick_skip)
+                    .run_if(in_state(MatchState::DisplayingPlayedCard)),
+            )
+            .add_systems(
+                OnExit(MatchState::Playing),
+                despawn_screen::<components::CardFocusHighlight>,
+            )
             .add_systems(
                 OnExit(MatchState::Paused),
                 despawn_screen::<components::OnPauseScreen>,
             )
+            .add_systems(
+                OnExit(MatchState::Finished),
+                despawn_screen::<components::OnGameOverScreen>,
+            )
             .add_systems(
                 OnExit(MatchState::Guessing),
                 despawn_screen::<components::GuessUI>,
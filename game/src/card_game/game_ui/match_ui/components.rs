@@ -1,5 +1,5 @@
 use bevy::{
-    ecs::{bundle::Bundle, component::Component}, render::mesh::Mesh2d, sprite::{ColorMaterial, MeshMaterial2d, Sprite}, transform::components::Transform
+    ecs::{bundle::Bundle, component::Component}, math::Vec2, render::mesh::Mesh2d, sprite::{ColorMaterial, MeshMaterial2d, Sprite}, time::Timer, transform::components::Transform
 };
 
 #[derive(Component)]
@@ -16,9 +16,32 @@ pub struct OnPauseScreen;
 #[derive(Component)]
 pub enum PauseButtonAction {
     ResumeGame,
+    BotSpeed,
+    AnimationSpeed,
     QuitToMainMenu,
 }
 
+#[derive(Component)]
+pub struct BotSpeedText;
+
+#[derive(Component)]
+pub struct AnimationSpeedText;
+
+#[derive(Component)]
+pub struct OnGameOverScreen;
+
+#[derive(Component)]
+pub struct HandoffScreen;
+
+#[derive(Component)]
+pub struct HandoffConfirmButton;
+
+#[derive(Component)]
+pub enum GameOverButtonAction {
+    PlayAgain,
+    BackToMenu,
+}
+
 #[derive(Component)]
 pub enum MatchButtonAction {
     RemoveGuess,
@@ -26,6 +49,12 @@ pub enum MatchButtonAction {
     ConfirmGuess,
 }
 
+#[derive(Component)]
+pub enum HistoryButtonAction {
+    Undo,
+    Redo,
+}
+
 #[derive(Component)]
 pub struct RemoveGuessButton;
 
@@ -48,6 +77,35 @@ pub struct CardDisplay {
     pub visible: VisibleCard,
 }
 
+// Slides a card across the table, the depth is left alone so stacking order is kept
+#[derive(Component)]
+pub struct CardTween {
+    pub start: Vec2,
+    pub end: Vec2,
+    // Seconds to wait before moving, so dealt cards arrive one after the other
+    pub delay: f32,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+// Turns a card over, the face is swapped in when the card is seen edge-on
+#[derive(Component)]
+pub struct CardFlip {
+    pub face: Option<Sprite>,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+// The card is showing its back, so revealing it later flips it over
+#[derive(Component)]
+pub struct FaceDown;
+
+#[derive(Component)]
+pub struct CollectingTrick;
+
+#[derive(Component)]
+pub struct CardFocusHighlight;
+
 #[derive(Component)]
 pub struct PlayArea(pub f32);
 
@@ -59,5 +117,34 @@ pub struct PlayAreaBundle {
     pub play_area: PlayArea,
 }
 
+// A seat around the table with its name, scores and face-down cards
 #[derive(Component)]
-pub struct PlayerInfoUI(pub usize);
\ No newline at end of file
+pub struct SeatDisplay(pub usize);
+
+#[derive(Component)]
+pub struct ViraCard(pub card_game_logic::game_logic::common::Card);
+
+#[derive(Component)]
+pub struct RoundInfoUI;
+
+#[derive(Component)]
+pub struct EliminationNotice(pub Timer);
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum MatchPanel {
+    Scoreboard,
+    TrickHistory,
+}
+
+#[derive(Component)]
+pub struct PanelToggle(pub MatchPanel);
+
+#[derive(Component)]
+pub struct PanelToggleText(pub MatchPanel);
+
+// The rows on show are kept so the panel is only rebuilt when they change
+#[derive(Component)]
+pub struct PanelBody {
+    pub panel: MatchPanel,
+    pub rows: Vec<Vec<String>>,
+}
@@ -0,0 +1,87 @@
+use std::f32::consts::PI;
+
+use bevy::{asset::Handle, ecs::system::Resource, image::Image, math::Vec2};
+
+use super::{CARD_HEIGHT, CARD_WIDTH};
+
+// Holding the handles keeps the sprites of the chosen deck loaded for the whole game
+#[derive(Resource)]
+pub struct DeckSprites {
+    pub _handles: Vec<Handle<Image>>,
+}
+
+// The local player whose hand may be shown, None until someone takes the device
+#[derive(Resource, Default)]
+pub struct RevealedPlayer(pub Option<usize>);
+
+// Where everything sits on the table, recomputed whenever the window is resized
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct TableLayout {
+    pub size: Vec2,
+    // The seat at the bottom of the screen, whose hand is laid out in full
+    pub viewer: usize,
+}
+
+impl Default for TableLayout {
+    fn default() -> Self {
+        Self {
+            size: Vec2::new(1280.0, 720.0),
+            viewer: 0,
+        }
+    }
+}
+
+impl TableLayout {
+    pub fn center(&self) -> Vec2 {
+        Vec2::new(0.0, self.size.y * 0.08)
+    }
+
+    pub fn play_area_radius(&self) -> f32 {
+        (self.size.x.min(self.size.y) * 0.2).max(90.0)
+    }
+
+    pub fn hand_y(&self) -> f32 {
+        -self.size.y / 2.0 + CARD_HEIGHT * 0.6
+    }
+
+    // The hand is centred and squeezed together when it would not fit the window
+    pub fn hand_position(&self, index: usize, count: usize) -> Vec2 {
+        let available = self.size.x * 0.8 - CARD_WIDTH;
+        let spacing = (CARD_WIDTH + 20.0).min(available / count.saturating_sub(1).max(1) as f32);
+        let x = (index as f32 - count.saturating_sub(1) as f32 / 2.0) * spacing;
+
+        Vec2::new(x, self.hand_y())
+    }
+
+    // The viewer sits below the play area, everyone else follows in turn order around the
+    // upper part of an ellipse, from the left side to the right
+    pub fn seat_position(&self, player_id: usize, player_count: usize) -> Vec2 {
+        let seat = (player_id + player_count - self.viewer) % player_count.max(1);
+        if seat == 0 {
+            return Vec2::new(0.0, self.hand_y() + CARD_HEIGHT / 2.0 + 24.0);
+        }
+
+        let progress = seat as f32 / player_count as f32;
+        let angle = (PI + 0.35) - (PI + 0.7) * progress;
+
+        self.center()
+            + Vec2::new(
+                angle.cos() * self.size.x * 0.4,
+                angle.sin() * self.size.y * 0.36,
+            )
+    }
+
+    // New cards are dealt from here and cancelled tricks are thrown here
+    pub fn deck_position(&self) -> Vec2 {
+        Vec2::new(-self.size.x / 2.0 + 90.0, self.size.y / 2.0 - 140.0)
+    }
+
+    pub fn vira_position(&self) -> Vec2 {
+        self.deck_position() + Vec2::new(CARD_WIDTH, 0.0)
+    }
+}
+
+// Index into the current player's hand, from left to right. None until the keys are used, so
+// mouse players never see the highlight
+#[derive(Resource, Default)]
+pub struct CardFocus(pub Option<usize>);
@@ -7,53 +7,101 @@ use bevy::{
     ecs::{
         entity::Entity,
         event::EventWriter,
-        query::{Added, Changed, With, Without},
-        system::{Commands, Query, Res, ResMut, Single},
+        change_detection::{DetectChanges, DetectChangesMut, Ref},
+        query::{Changed, With, Without},
+        event::EventReader,
+        system::{Commands, Local, Query, Res, ResMut, Single, SystemParam},
+    },
+    hierarchy::{BuildChildren, ChildBuild, DespawnRecursiveExt},
+    input::{
+        ButtonInput,
+        keyboard::KeyCode,
+        mouse::{MouseButton, MouseScrollUnit, MouseWheel},
     },
-    hierarchy::{BuildChildren, ChildBuild},
-    input::{ButtonInput, mouse::MouseButton},
     math::{Vec2, primitives::Annulus},
     render::{
         camera::Camera,
         mesh::{Mesh, Mesh2d},
+        view::Visibility,
     },
-    sprite::{ColorMaterial, MeshMaterial2d},
-    text::{TextColor, TextFont},
+    sprite::{ColorMaterial, MeshMaterial2d, Sprite},
+    state::state::{NextState, State},
+    text::{Text2d, TextColor, TextFont},
+    time::{Time, Timer, TimerMode},
     transform::components::{GlobalTransform, Transform},
     ui::{
-        AlignItems, BackgroundColor, FlexDirection, Interaction, JustifyContent, Node,
-        PositionType, UiRect, Val,
+        AlignItems, BackgroundColor, Display, FlexDirection, FocusPolicy, GlobalZIndex,
+        Interaction, JustifyContent, Node, Overflow, PositionType, ScrollPosition, UiRect, Val,
         widget::{Button, Text},
     },
     utils::default,
     window::Window,
 };
 
+use card_game_logic::{
+    bot::SeatType,
+    game_logic::{
+        GameLogic,
+        common::{Card as CardStruct, GameOutcome, Rank, Suit},
+        history::{PlayerStats, TrickRecord},
+    },
+};
+
 use crate::card_game::{
+    GameLogicRes, GameSettings, GameState,
     game_logic_runner::{
-        components::{Card, CurrentPlayer, Guess, MaxGuess, PlayerInfo, TopPlayedCard},
-        events::{CardPlayed, PlayerGuessed},
+        MatchState,
+        components::{
+            Card, CurrentPlayer, DisplayPlayedCardTimer, Guess, MaxGuess, PlayerInfo,
+            TopPlayedCard,
+        },
+        events::{
+            CardPlayed, GameEnded, PauseRequested, PlayerEliminated, PlayerGuessed, RedoRequested,
+            ResumeRequested, SkipTrickRequested, UndoRequested,
+        },
+        game_logic_facade::GameLogicFacade,
     },
     game_ui::{
-        DISABLED_BUTTON, NORMAL_BUTTON, TEXT_COLOR, asset_loader::AssetLoader,
-        components::ButtonDisabled, match_ui::components::CardDisplay,
+        DISABLED_BUTTON, NORMAL_BUTTON, TEXT_COLOR,
+        asset_loader::AssetLoader,
+        components::ButtonDisabled,
+        controls::resources::{ButtonFocus, ControlAction, ControlInput},
+        match_ui::components::CardDisplay,
+    },
+    preferences::{
+        ANIMATION_SPEEDS, BOT_SPEEDS, Preferences, animation_speed_text, bot_speed_text, next_in,
     },
 };
 
+use super::resources::{CardFocus, DeckSprites, RevealedPlayer, TableLayout};
+use super::{CARD_HEIGHT, CARD_WIDTH};
+
 use super::components::{
-    AddGuessButton, CardSelected, ConfirmGuessButton, GuessUI, MatchButtonAction, MatchUI,
-    OnPauseScreen, PauseButtonAction, PlayArea, PlayAreaBundle, PlayerInfoUI, RemoveGuessButton,
-    VisibleCard,
+    AddGuessButton, CardSelected, ConfirmGuessButton, GameOverButtonAction, GuessUI,
+    HandoffConfirmButton, HandoffScreen, HistoryButtonAction, MatchButtonAction, MatchUI,
+    OnGameOverScreen,
+    AnimationSpeedText, BotSpeedText, CardFlip, CardTween, CollectingTrick, FaceDown,
+    CardFocusHighlight,
+    OnPauseScreen, PauseButtonAction, PlayArea, PlayAreaBundle, RemoveGuessButton, SeatDisplay,
+    EliminationNotice, MatchPanel, PanelBody, PanelToggle, PanelToggleText, RoundInfoUI,
+    ViraCard, VisibleCard,
 };
 
-const CARD_WIDTH: f32 = 130.0;
-const CARD_HEIGHT: f32 = 202.0;
+// Animation lengths at normal speed, in seconds
+const CARD_MOVE_SECONDS: f32 = 0.35;
+const CARD_FLIP_SECONDS: f32 = 0.3;
+const DEAL_STAGGER_SECONDS: f32 = 0.08;
+const TRICK_COLLECT_SECONDS: f32 = 0.5;
 
-pub fn match_ui_setup(mut commands: Commands, current_player: Single<Entity, With<CurrentPlayer>>) {
+pub fn match_ui_setup(
+    mut commands: Commands,
+    current_player: Single<Entity, With<CurrentPlayer>>,
+    game_settings: Res<GameSettings>,
+) {
     let mut entity = commands.entity(*current_player);
 
     entity.insert((
-        Text::new("Player 1's turn"),
+        Text::new(format!("{}'s turn", game_settings.player_name(0))),
         Node {
             position_type: PositionType::Absolute,
             top: Val::Px(12.0),
@@ -62,6 +110,463 @@ pub fn match_ui_setup(mut commands: Commands, current_player: Single<Entity, Wit
         },
         MatchUI,
     ));
+
+    commands.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(44.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        RoundInfoUI,
+        MatchUI,
+    ));
+}
+
+pub fn preload_deck_sprites(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    asset_server: Res<AssetServer>,
+) {
+    let deck = game_logic.0.get_rules().build_deck();
+
+    commands.insert_resource(DeckSprites {
+        _handles: asset_server.load_deck_sprites(&deck),
+    });
+}
+
+pub fn update_round_info(
+    game_logic: Res<GameLogicRes>,
+    mut round_info_query: Query<&mut Text, With<RoundInfoUI>>,
+) {
+    let round_number = game_logic.0.get_round_number();
+    let round_info = match game_logic.0.get_total_rounds() {
+        Some(total_rounds) => format!("Round {} of {}", round_number, total_rounds),
+        None => format!("Round {}", round_number),
+    };
+
+    for mut text in round_info_query.iter_mut() {
+        if text.0 != round_info {
+            text.0 = round_info.clone();
+        }
+    }
+}
+
+pub fn history_ui_setup(mut commands: Commands) {
+    let button_node = Node {
+        width: Val::Px(110.0),
+        height: Val::Px(40.0),
+        margin: UiRect::all(Val::Px(5.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let button_text_font = TextFont {
+        font_size: 20.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                left: Val::Px(12.0),
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            MatchUI,
+        ))
+        .with_children(|parent| {
+            for (label, action) in [
+                ("Undo", HistoryButtonAction::Undo),
+                ("Redo", HistoryButtonAction::Redo),
+            ] {
+                parent
+                    .spawn((
+                        Button,
+                        button_node.clone(),
+                        BackgroundColor(DISABLED_BUTTON),
+                        ButtonDisabled,
+                        action,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(label),
+                            button_text_font.clone(),
+                            TextColor(TEXT_COLOR),
+                        ));
+                    });
+            }
+        });
+}
+
+// Scoreboard and trick history in the top right corner, each can be folded away
+pub fn panels_ui_setup(mut commands: Commands) {
+    let header_font = TextFont {
+        font_size: 20.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                width: Val::Px(PANEL_WIDTH),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            MatchUI,
+        ))
+        .with_children(|parent| {
+            // The scoreboard starts open, the trick history only when asked for
+            let panels = [
+                (MatchPanel::Scoreboard, true),
+                (MatchPanel::TrickHistory, false),
+            ];
+            for (panel, open) in panels {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            height: Val::Px(32.0),
+                            margin: UiRect::top(Val::Px(6.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(NORMAL_BUTTON),
+                        PanelToggle(panel),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(panel_title(panel, open)),
+                            header_font.clone(),
+                            TextColor(TEXT_COLOR),
+                            PanelToggleText(panel),
+                        ));
+                    });
+
+                // Wider tables scroll sideways with the mouse wheel, so every match fits
+                parent.spawn((
+                    Node {
+                        display: if open { Display::Flex } else { Display::None },
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(6.0)),
+                        overflow: Overflow::scroll_x(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                    Interaction::default(),
+                    ScrollPosition::default(),
+                    PanelBody {
+                        panel,
+                        rows: Vec::new(),
+                    },
+                ));
+            }
+        });
+}
+
+const PANEL_WIDTH: f32 = 360.0;
+// Narrowest a score column gets before the panel starts to scroll
+const MIN_COLUMN_WIDTH: f32 = 48.0;
+const PANEL_SCROLL_LINE: f32 = 24.0;
+
+fn panel_title(panel: MatchPanel, open: bool) -> String {
+    let title = match panel {
+        MatchPanel::Scoreboard => "Scoreboard",
+        MatchPanel::TrickHistory => "Trick history",
+    };
+
+    format!("{} {}", title, if open { "[-]" } else { "[+]" })
+}
+
+pub fn toggle_panels(
+    interaction_query: Query<(&Interaction, &PanelToggle), (Changed<Interaction>, With<Button>)>,
+    mut body_query: Query<(&PanelBody, &mut Node)>,
+    mut title_query: Query<(&PanelToggleText, &mut Text)>,
+) {
+    for (interaction, toggle) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        for (body, mut node) in body_query.iter_mut() {
+            if body.panel != toggle.0 {
+                continue;
+            }
+
+            let open = node.display == Display::None;
+            node.display = if open { Display::Flex } else { Display::None };
+
+            for (title, mut text) in title_query.iter_mut() {
+                if title.0 == toggle.0 {
+                    text.0 = panel_title(toggle.0, open);
+                }
+            }
+        }
+    }
+}
+
+pub fn scroll_panels(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut body_query: Query<(&Interaction, &mut ScrollPosition), With<PanelBody>>,
+) {
+    for event in mouse_wheel_events.read() {
+        let (x, y) = match event.unit {
+            MouseScrollUnit::Line => (event.x * PANEL_SCROLL_LINE, event.y * PANEL_SCROLL_LINE),
+            MouseScrollUnit::Pixel => (event.x, event.y),
+        };
+
+        // Either wheel direction moves along the matches
+        for (interaction, mut scroll_position) in body_query.iter_mut() {
+            if *interaction != Interaction::None {
+                scroll_position.offset_x -= x + y;
+            }
+        }
+    }
+}
+
+pub fn update_panels(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    game_settings: Res<GameSettings>,
+    mut body_query: Query<(Entity, &mut PanelBody, &mut ScrollPosition)>,
+) {
+    let cell_font = TextFont {
+        font_size: 16.0,
+        ..default()
+    };
+
+    for (entity, mut body, mut scroll_position) in body_query.iter_mut() {
+        let rows = match body.panel {
+            MatchPanel::Scoreboard => scoreboard_rows(&game_logic.0, &game_settings),
+            MatchPanel::TrickHistory => trick_history_rows(&game_logic.0, &game_settings),
+        };
+        if body.rows == rows {
+            continue;
+        }
+
+        // Names take the first column of the scoreboard, the other columns share what is left
+        let column_count = rows.iter().map(Vec::len).max().unwrap_or(1);
+        let first_width = if column_count > 1 { 100.0 } else { PANEL_WIDTH - 12.0 };
+        let other_width = ((PANEL_WIDTH - 12.0 - first_width) / (column_count - 1).max(1) as f32)
+            .max(MIN_COLUMN_WIDTH);
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for row in &rows {
+                    parent
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Row,
+                            margin: UiRect::vertical(Val::Px(2.0)),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (column, cell) in row.iter().enumerate() {
+                                parent.spawn((
+                                    Text::new(cell.clone()),
+                                    cell_font.clone(),
+                                    TextColor(TEXT_COLOR),
+                                    Node {
+                                        width: Val::Px(if column == 0 {
+                                            first_width
+                                        } else {
+                                            other_width
+                                        }),
+                                        flex_shrink: 0.0,
+                                        ..default()
+                                    },
+                                ));
+                            }
+                        });
+                }
+            });
+
+        // A new match moves the view to the newest columns, the layout keeps it in range
+        if rows.first().map(Vec::len) != body.rows.first().map(Vec::len) {
+            scroll_position.offset_x = f32::MAX;
+        }
+        body.rows = rows;
+    }
+}
+
+// Guesses against tricks won for every match so far and the one being played, then lives
+fn scoreboard_rows(game_logic: &GameLogicFacade, game_settings: &GameSettings) -> Vec<Vec<String>> {
+    let match_history = game_logic.get_match_history();
+
+    let mut header = vec!["Player".to_string()];
+    header.extend(
+        match_history
+            .iter()
+            .map(|record| format!("R{}", record.round_number)),
+    );
+    header.push("Now".to_string());
+    header.push("Lives".to_string());
+
+    let rows = (0..game_logic.get_player_count()).map(|player_id| {
+        let lives = game_logic.get_player_lives(player_id);
+
+        let mut row = vec![game_settings.player_name(player_id)];
+        row.extend(match_history.iter().map(|record| {
+            if record.played(player_id) {
+                format!("{}/{}", record.guesses[player_id], record.wins[player_id])
+            } else {
+                "-".to_string()
+            }
+        }));
+        row.push(if lives > 0 {
+            format!(
+                "{}/{}",
+                game_logic.get_player_guess(player_id),
+                game_logic.get_player_wins(player_id)
+            )
+        } else {
+            "-".to_string()
+        });
+        row.push(if lives > 0 { lives.to_string() } else { "Out".to_string() });
+        row
+    });
+
+    std::iter::once(header).chain(rows).collect()
+}
+
+// The tricks of the match being played, newest first. Before its first trick the previous
+// match is shown instead
+fn trick_history_rows(
+    game_logic: &GameLogicFacade,
+    game_settings: &GameSettings,
+) -> Vec<Vec<String>> {
+    let tricks = match game_logic.get_trick_history() {
+        tricks if !tricks.is_empty() => tricks,
+        _ => match game_logic.get_match_history().last() {
+            Some(record) => &record.tricks,
+            None => return vec![vec!["No tricks played yet".to_string()]],
+        },
+    };
+
+    tricks
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, trick)| {
+            let cards: Vec<String> = trick
+                .cards
+                .iter()
+                .map(|played_card| {
+                    format!(
+                        "{} {}{}",
+                        game_settings.player_name(played_card.player_id),
+                        card_label(&played_card.card),
+                        if played_card.cancelled { " (cancelled)" } else { "" }
+                    )
+                })
+                .collect();
+            let winner = match trick.winner {
+                Some(winner) => format!("won by {}", game_settings.player_name(winner)),
+                None => "nobody won".to_string(),
+            };
+
+            vec![format!("{}. {}, {}", index + 1, cards.join(", "), winner)]
+        })
+        .collect()
+}
+
+// Short enough for a trick of eight cards to fit in the panel, e.g. "10H" or "QS"
+fn card_label(card: &CardStruct) -> String {
+    let rank = match card.1 {
+        Rank::Ace => "A",
+        Rank::Two => "2",
+        Rank::Three => "3",
+        Rank::Four => "4",
+        Rank::Five => "5",
+        Rank::Six => "6",
+        Rank::Seven => "7",
+        Rank::Eight => "8",
+        Rank::Nine => "9",
+        Rank::Ten => "10",
+        Rank::Jack => "J",
+        Rank::Queen => "Q",
+        Rank::King => "K",
+    };
+    let suit = match card.0 {
+        Suit::Clubs => "C",
+        Suit::Hearts => "H",
+        Suit::Diamonds => "D",
+        Suit::Spades => "S",
+    };
+
+    format!("{}{}", rank, suit)
+}
+
+// Ctrl+Z undoes, Ctrl+Y or Ctrl+Shift+Z redoes
+pub fn handle_history_action(
+    interaction_query: Query<
+        (&Interaction, &HistoryButtonAction, Option<&ButtonDisabled>),
+        (Changed<Interaction>, With<Button>),
+    >,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut undo_events: EventWriter<UndoRequested>,
+    mut redo_events: EventWriter<RedoRequested>,
+) {
+    for (interaction, button_action, disabled) in &interaction_query {
+        if *interaction == Interaction::Pressed && disabled.is_none() {
+            match button_action {
+                HistoryButtonAction::Undo => {
+                    undo_events.send(UndoRequested);
+                }
+                HistoryButtonAction::Redo => {
+                    redo_events.send(RedoRequested);
+                }
+            }
+        }
+    }
+
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !ctrl {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)) {
+        redo_events.send(RedoRequested);
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        undo_events.send(UndoRequested);
+    }
+}
+
+pub fn enable_disable_history_buttons(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    match_state: Res<State<MatchState>>,
+    button_query: Query<(Entity, &HistoryButtonAction, Option<&ButtonDisabled>), With<Button>>,
+) {
+    // Moves can only be taken back while someone is about to guess or play
+    let can_act = matches!(
+        match_state.get(),
+        MatchState::Guessing | MatchState::Playing
+    );
+
+    for (entity, action, disabled) in &button_query {
+        let enabled = can_act
+            && match action {
+                HistoryButtonAction::Undo => game_logic.0.can_undo(),
+                HistoryButtonAction::Redo => game_logic.0.can_redo(),
+            };
+
+        if enabled && disabled.is_some() {
+            commands.entity(entity).remove::<ButtonDisabled>();
+        } else if !enabled && disabled.is_none() {
+            commands.entity(entity).insert(ButtonDisabled);
+        }
+    }
 }
 
 pub fn guess_ui_setup(mut commands: Commands) {
@@ -173,7 +678,11 @@ pub fn guess_ui_setup(mut commands: Commands) {
         });
 }
 
-pub fn pause_setup(mut commands: Commands) {
+pub fn pause_setup(
+    mut commands: Commands,
+    game_state: Res<State<GameState>>,
+    game_settings: Res<GameSettings>,
+) {
     // Common style for all buttons on the screen
     let button_node = Node {
         width: Val::Px(300.0),
@@ -198,6 +707,8 @@ pub fn pause_setup(mut commands: Commands) {
                 justify_content: JustifyContent::Center,
                 ..default()
             },
+            // Stays above the handoff screen
+            GlobalZIndex(2),
             OnPauseScreen,
         ))
         .with_children(|parent| {
@@ -239,6 +750,41 @@ pub fn pause_setup(mut commands: Commands) {
                             ));
                         });
 
+                    // Bots only run in local games
+                    if *game_state.get() == GameState::LocalGame {
+                        parent
+                            .spawn((
+                                Button,
+                                button_node.clone(),
+                                BackgroundColor(NORMAL_BUTTON),
+                                PauseButtonAction::BotSpeed,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(bot_speed_text(game_settings.bot_think_delay)),
+                                    button_text_font.clone(),
+                                    TextColor(TEXT_COLOR),
+                                    BotSpeedText,
+                                ));
+                            });
+                    }
+
+                    parent
+                        .spawn((
+                            Button,
+                            button_node.clone(),
+                            BackgroundColor(NORMAL_BUTTON),
+                            PauseButtonAction::AnimationSpeed,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new(animation_speed_text(game_settings.animation_speed)),
+                                button_text_font.clone(),
+                                TextColor(TEXT_COLOR),
+                                AnimationSpeedText,
+                            ));
+                        });
+
                     parent
                         .spawn((
                             Button,
@@ -257,45 +803,577 @@ pub fn pause_setup(mut commands: Commands) {
         });
 }
 
-pub fn display_player_cards(
+pub fn handle_pause_key(
+    controls: ControlInput,
+    match_state: Res<State<MatchState>>,
+    mut pause_events: EventWriter<PauseRequested>,
+    mut resume_events: EventWriter<ResumeRequested>,
+) {
+    if !controls.just_pressed(ControlAction::Pause) {
+        return;
+    }
+
+    if *match_state.get() == MatchState::Paused {
+        resume_events.send(ResumeRequested);
+    } else {
+        pause_events.send(PauseRequested);
+    }
+}
+
+pub fn handle_pause_action(
+    interaction_query: Query<
+        (&Interaction, &PauseButtonAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut bot_speed_text_query: Query<&mut Text, (With<BotSpeedText>, Without<AnimationSpeedText>)>,
+    mut animation_speed_text_query: Query<&mut Text, With<AnimationSpeedText>>,
+    mut preferences: ResMut<Preferences>,
+    mut resume_events: EventWriter<ResumeRequested>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button_action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button_action {
+            PauseButtonAction::ResumeGame => {
+                resume_events.send(ResumeRequested);
+            }
+            // Kept as preferences, so the choice is remembered for the next games
+            PauseButtonAction::BotSpeed => {
+                let delays = BOT_SPEEDS.map(|(_, delay)| delay);
+                preferences.bot_think_delay = next_in(&delays, preferences.bot_think_delay);
+
+                for mut text in bot_speed_text_query.iter_mut() {
+                    text.0 = bot_speed_text(preferences.bot_think_delay);
+                }
+            }
+            PauseButtonAction::AnimationSpeed => {
+                let speeds = ANIMATION_SPEEDS.map(|(_, speed)| speed);
+                preferences.animation_speed = next_in(&speeds, preferences.animation_speed);
+
+                for mut text in animation_speed_text_query.iter_mut() {
+                    text.0 = animation_speed_text(preferences.animation_speed);
+                }
+            }
+            // Leaving the game state tears the match down
+            PauseButtonAction::QuitToMainMenu => game_state.set(GameState::Menu),
+        }
+    }
+}
+
+pub fn reset_revealed_player(mut commands: Commands) {
+    commands.insert_resource(RevealedPlayer::default());
+}
+
+// What the cards on the table are laid out for, they are dealt again whenever any of it changes
+#[derive(SystemParam)]
+pub struct CardLayoutState<'w> {
+    current_player: Single<'w, Ref<'static, CurrentPlayer>>,
+    match_state: Res<'w, State<MatchState>>,
+    revealed_player: Res<'w, RevealedPlayer>,
+    table_layout: ResMut<'w, TableLayout>,
+}
+
+impl CardLayoutState<'_> {
+    // The own card is revealed when the blind round moves from guessing to playing
+    fn is_changed(&self) -> bool {
+        self.current_player.is_changed()
+            || self.match_state.is_changed()
+            || self.revealed_player.is_changed()
+            || self.table_layout.is_changed()
+    }
+}
+
+pub fn display_player_cards(
+    mut commands: Commands,
+    mut layout_state: CardLayoutState,
+    cards_query: Query<(Entity, &Card, Option<&Transform>, Option<&FaceDown>)>,
+    game_logic: Res<GameLogicRes>,
+    asset_server: Res<AssetServer>,
+    game_settings: Res<GameSettings>,
+    handoff_screen_query: Query<Entity, With<HandoffScreen>>,
+) {
+    if !layout_state.is_changed() {
+        return;
+    }
+
+    let viewer_id = layout_state.current_player.0;
+    let revealed_player = &layout_state.revealed_player;
+
+    // Bots never reveal their hand, so the last human hand stays on screen
+    if game_settings.seat_type(viewer_id) != SeatType::Human {
+        return;
+    }
+
+    // With several people at the table, every hand stays hidden until the next one takes over
+    let handoff_pending = game_settings.hot_seat_handoff
+        && game_settings.human_count() > 1
+        && revealed_player.0 != Some(viewer_id);
+    if handoff_pending && handoff_screen_query.is_empty() {
+        spawn_handoff_screen(&mut commands, &game_settings.player_name(viewer_id));
+    }
+
+    // The table turns so the player holding the device sits at the bottom
+    let table_layout = &mut layout_state.table_layout;
+    if table_layout.viewer != viewer_id {
+        table_layout.viewer = viewer_id;
+    }
+
+    let view = game_logic.0.get_player_view(viewer_id);
+    let player_count = game_logic.0.get_player_count();
+    let hand_count = cards_query
+        .iter()
+        .filter(|(_, card, _, _)| card.player_id == Some(viewer_id))
+        .count();
+    let mut hand_index = 0;
+    let mut others_index = vec![0; player_count];
+    let mut dealt_count = 0;
+    const OTHERS_SCALE: f32 = 0.4;
+
+    for (entity_id, card, transform, face_down) in cards_query.iter() {
+        let Some(owner_id) = card.player_id else {
+            continue;
+        };
+
+        let mut entity = commands.entity(entity_id);
+        entity
+            .remove::<(CardDisplay, CardTween, CardFlip)>()
+            .despawn_descendants();
+
+        if handoff_pending {
+            continue;
+        }
+
+        // Cards already on screen slide to their new place, the others are dealt from the deck
+        let target = if owner_id == viewer_id {
+            table_layout.hand_position(hand_index, hand_count)
+        } else {
+            // Shown above the seat, next to each other
+            table_layout.seat_position(owner_id, player_count)
+                + Vec2::new(
+                    others_index[owner_id] as f32 * (CARD_WIDTH * OTHERS_SCALE + 10.0),
+                    CARD_HEIGHT * OTHERS_SCALE * 0.5 + 40.0,
+                )
+        };
+        let (start, delay) = match transform {
+            Some(transform) => (transform.translation.truncate(), 0.0),
+            None => {
+                dealt_count += 1;
+                (
+                    table_layout.deck_position(),
+                    (dealt_count - 1) as f32 * DEAL_STAGGER_SECONDS,
+                )
+            }
+        };
+
+        let visible = view.seats[owner_id].hand.is_some();
+        if owner_id == viewer_id {
+            let card_back = asset_server.card_back_sprite(Vec2::new(CARD_WIDTH, CARD_HEIGHT));
+            entity.insert((
+                CardDisplay {
+                    sprite: if visible && face_down.is_none() {
+                        asset_server.load_card_sprite(&card.card)
+                    } else {
+                        card_back
+                    },
+                    transform: Transform::from_xyz(start.x, start.y, 0.0),
+                    visible: VisibleCard,
+                },
+                card_tween(start, target, delay, CARD_MOVE_SECONDS),
+            ));
+
+            if !visible {
+                entity.insert(FaceDown);
+            } else if face_down.is_some() {
+                // The own card of the blind round is turned over once the guesses are in
+                entity.insert(card_flip(asset_server.load_card_sprite(&card.card)));
+            }
+            hand_index += 1;
+        } else if visible {
+            // Other hands are only visible in the blind round
+            entity.insert((
+                CardDisplay {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(
+                            CARD_WIDTH * OTHERS_SCALE,
+                            CARD_HEIGHT * OTHERS_SCALE,
+                        )),
+                        ..asset_server.load_card_sprite(&card.card)
+                    },
+                    transform: Transform::from_xyz(start.x, start.y, 0.0),
+                    visible: VisibleCard,
+                },
+                card_tween(start, target, delay, CARD_MOVE_SECONDS),
+            ));
+            others_index[owner_id] += 1;
+        }
+    }
+}
+
+fn spawn_handoff_screen(commands: &mut Commands, player_name: &str) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            // Keeps the guess buttons underneath from being pressed
+            FocusPolicy::Block,
+            GlobalZIndex(1),
+            HandoffScreen,
+            MatchUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Pass the device to {}", player_name)),
+                TextFont {
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(TEXT_COLOR),
+                Node {
+                    margin: UiRect::all(Val::Px(50.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(65.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                    HandoffConfirmButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("I'm {}", player_name)),
+                        TextFont {
+                            font_size: 25.0,
+                            ..default()
+                        },
+                        TextColor(TEXT_COLOR),
+                    ));
+                });
+        });
+}
+
+// The confirm key works as well as the button
+pub fn handle_handoff_confirm(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<HandoffConfirmButton>)>,
+    controls: ControlInput,
+    current_player: Single<&CurrentPlayer>,
+    mut revealed_player: ResMut<RevealedPlayer>,
+    handoff_screen_query: Query<Entity, With<HandoffScreen>>,
+) {
+    if handoff_screen_query.is_empty() {
+        return;
+    }
+
+    let confirmed = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+        || controls.just_pressed(ControlAction::Confirm);
+    if !confirmed {
+        return;
+    }
+
+    for entity in handoff_screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    revealed_player.0 = Some(current_player.0);
+}
+
+pub fn display_played_cards(
+    mut commands: Commands,
+    cards_query: Query<(Entity, Ref<Card>, Option<&Transform>), Without<CollectingTrick>>,
+    game_logic: Res<GameLogicRes>,
+    table_layout: Res<TableLayout>,
+    asset_server: Res<AssetServer>,
+) {
+    // Cards that just reached the table are the ones that were changed this frame, and after a
+    // resize every card is placed again
+    let relayout = table_layout.is_changed();
+    let is_new_on_table = |card: &Ref<Card>, transform: Option<&Transform>| {
+        relayout || card.is_changed() || transform.is_none()
+    };
+
+    let mut played_count = cards_query
+        .iter()
+        .filter(|(_, card, transform)| {
+            card.player_id.is_none() && !is_new_on_table(card, *transform)
+        })
+        .count();
+
+    for (entity_id, card, transform) in cards_query.iter() {
+        if card.player_id.is_some() || !is_new_on_table(&card, transform) {
+            continue;
+        }
+
+        let slot = table_layout.center() + Vec2::new((played_count as f32 - 1.5) * 30.0, 0.0);
+
+        match transform {
+            // Dropped by the player or moved by a resize, it only needs to settle into its slot
+            Some(transform) => {
+                commands.entity(entity_id).insert(card_tween(
+                    transform.translation.truncate(),
+                    slot,
+                    0.0,
+                    CARD_MOVE_SECONDS,
+                ));
+            }
+            // Played without being dragged (e.g. by bots), it comes face down from the seat
+            None => {
+                let start = played_by(&game_logic.0, &card.card)
+                    .map(|player_id| {
+                        table_layout.seat_position(player_id, game_logic.0.get_player_count())
+                    })
+                    .unwrap_or(table_layout.deck_position());
+
+                commands.entity(entity_id).insert((
+                    CardDisplay {
+                        sprite: asset_server.card_back_sprite(Vec2::new(CARD_WIDTH, CARD_HEIGHT)),
+                        transform: Transform::from_xyz(start.x, start.y, 0.0),
+                        visible: VisibleCard,
+                    },
+                    card_tween(start, slot, 0.0, CARD_MOVE_SECONDS),
+                    card_flip(asset_server.load_card_sprite(&card.card)),
+                ));
+            }
+        }
+        played_count += 1;
+    }
+}
+
+// Once the trick has been shown for a while, its cards slide over to the winner
+pub fn collect_trick(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    table_layout: Res<TableLayout>,
+    display_timer: Single<&DisplayPlayedCardTimer>,
+    cards_query: Query<(Entity, &Card, &Transform), Without<CollectingTrick>>,
+) {
+    if display_timer.timer.remaining_secs() > TRICK_COLLECT_SECONDS {
+        return;
+    }
+
+    // Tricks nobody won are thrown on the discard pile by the deck
+    let target = last_trick(&game_logic.0)
+        .and_then(|trick| trick.winner)
+        .map(|winner| table_layout.seat_position(winner, game_logic.0.get_player_count()))
+        .unwrap_or(table_layout.deck_position());
+
+    for (entity_id, card, transform) in cards_query.iter() {
+        if card.player_id.is_some() {
+            continue;
+        }
+
+        // The trick can be cleared before this lands, so the card may already be gone
+        commands.entity(entity_id).try_insert((
+            card_tween(
+                transform.translation.truncate(),
+                target,
+                0.0,
+                TRICK_COLLECT_SECONDS,
+            ),
+            CollectingTrick,
+        ));
+    }
+}
+
+// Clicking or pressing a key clears the trick right away
+pub fn request_trick_skip(
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    controls: ControlInput,
+    mut skip_events: EventWriter<SkipTrickRequested>,
+) {
+    if buttons.just_pressed(MouseButton::Left)
+        || keys.just_pressed(KeyCode::Space)
+        || controls.just_pressed(ControlAction::Confirm)
+    {
+        skip_events.send(SkipTrickRequested);
+    }
+}
+
+pub fn animate_card_tweens(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_settings: Res<GameSettings>,
+    mut tween_query: Query<(Entity, &mut CardTween, &mut Transform)>,
+) {
+    let delta = time.delta_secs() * game_settings.animation_speed;
+
+    for (entity_id, mut tween, mut transform) in tween_query.iter_mut() {
+        tween.elapsed += delta;
+        let progress = ((tween.elapsed - tween.delay) / tween.duration).clamp(0.0, 1.0);
+        // Eases out, so cards slow down as they land
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let position = tween.start.lerp(tween.end, eased);
+
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        if progress >= 1.0 {
+            commands.entity(entity_id).remove::<CardTween>();
+        }
+    }
+}
+
+pub fn animate_card_flips(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_settings: Res<GameSettings>,
+    mut flip_query: Query<(Entity, &mut CardFlip, &mut Sprite, &mut Transform)>,
+) {
+    let delta = time.delta_secs() * game_settings.animation_speed;
+
+    for (entity_id, mut flip, mut sprite, mut transform) in flip_query.iter_mut() {
+        flip.elapsed += delta;
+        let progress = (flip.elapsed / flip.duration).clamp(0.0, 1.0);
+
+        // The card narrows to nothing and opens up again showing its face
+        if let Some(face) = flip.face.take_if(|_| progress >= 0.5) {
+            *sprite = face;
+        }
+        transform.scale.x = (1.0 - 2.0 * progress).abs();
+
+        if progress >= 1.0 {
+            transform.scale.x = 1.0;
+            commands
+                .entity(entity_id)
+                .remove::<(CardFlip, FaceDown)>();
+        }
+    }
+}
+
+fn card_tween(start: Vec2, end: Vec2, delay: f32, duration: f32) -> CardTween {
+    CardTween {
+        start,
+        end,
+        delay,
+        duration,
+        elapsed: 0.0,
+    }
+}
+
+fn card_flip(face: Sprite) -> CardFlip {
+    CardFlip {
+        face: Some(face),
+        duration: CARD_FLIP_SECONDS,
+        elapsed: 0.0,
+    }
+}
+
+// A finished trick leaves the played cards, and a finished match the trick history
+fn last_trick(game_logic: &GameLogicFacade) -> Option<&TrickRecord> {
+    game_logic.get_trick_history().last().or_else(|| {
+        game_logic
+            .get_match_history()
+            .last()
+            .and_then(|record| record.tricks.last())
+    })
+}
+
+fn played_by(game_logic: &GameLogicFacade, card: &CardStruct) -> Option<usize> {
+    game_logic
+        .get_played_cards()
+        .iter()
+        .chain(last_trick(game_logic).map_or(&[][..], |trick| &trick.cards))
+        .find(|played_card| played_card.card == *card)
+        .map(|played_card| played_card.player_id)
+}
+
+pub fn display_vira(
     mut commands: Commands,
-    current_player_query: Query<&CurrentPlayer, Changed<CurrentPlayer>>,
-    visible_cards_query: Query<(Entity, &Card), With<VisibleCard>>,
-    hidden_cards_query: Query<(Entity, &Card), Without<VisibleCard>>,
+    game_logic: Res<GameLogicRes>,
+    table_layout: Res<TableLayout>,
+    mut vira_query: Query<(Entity, &ViraCard, &mut Transform)>,
     asset_server: Res<AssetServer>,
 ) {
-    let mut inital_x = -300.0;
-    const SPACING: f32 = 20.0 + CARD_WIDTH;
-    for current_player in current_player_query.iter() {
-        for (entity_id, card) in hidden_cards_query.iter() {
-            match card.player_id {
-                Some(card_player_id) => {
-                    let mut entity = commands.entity(entity_id);
-
-                    if card_player_id == current_player.0 {
-                        entity.insert(CardDisplay {
-                            sprite: asset_server.load_card_sprite(&card.card),
-                            transform: Transform::from_xyz(inital_x, -200.0, 0.0),
-                            visible: VisibleCard,
-                        });
-                        inital_x += SPACING;
-                    }
-                }
-                None => continue,
+    let vira = game_logic.0.get_vira();
+    let position = table_layout.vira_position();
+
+    // A new vira is turned up on every match, so the old one is replaced
+    for (entity_id, displayed, mut transform) in vira_query.iter_mut() {
+        if Some(displayed.0) == vira {
+            if table_layout.is_changed() {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
             }
+            return;
         }
 
-        for (entity_id, card) in visible_cards_query.iter() {
-            match card.player_id {
-                Some(card_player_id) => {
-                    let mut entity = commands.entity(entity_id);
+        commands.entity(entity_id).despawn_recursive();
+    }
 
-                    if card_player_id != current_player.0 {
-                        entity.remove::<CardDisplay>();
-                    }
-                }
-                None => continue,
-            }
+    let Some(vira) = vira else {
+        return;
+    };
+
+    let size = Vec2::new(CARD_WIDTH * 0.75, CARD_HEIGHT * 0.75);
+    commands
+        .spawn((
+            asset_server.card_back_sprite(size),
+            Transform::from_xyz(position.x, position.y, 0.0),
+            card_flip(Sprite {
+                custom_size: Some(size),
+                ..asset_server.load_card_sprite(&vira)
+            }),
+            ViraCard(vira),
+            MatchUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text2d::new("Vira"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(TEXT_COLOR),
+                Transform::from_xyz(0.0, CARD_HEIGHT * 0.5, 0.0),
+            ));
+        });
+}
+
+pub fn dim_cancelled_cards(
+    game_logic: Res<GameLogicRes>,
+    // Cards still being turned over show their back, which keeps its own colour
+    mut card_query: Query<(&Card, &mut Sprite), (With<VisibleCard>, Without<CardFlip>)>,
+) {
+    for (card, mut sprite) in card_query.iter_mut() {
+        if card.player_id.is_some() {
+            continue;
+        }
+
+        let cancelled = game_logic
+            .0
+            .get_played_cards()
+            .iter()
+            .any(|played_card| played_card.card == card.card && played_card.cancelled);
+        let color = if cancelled {
+            Color::srgb(0.5, 0.5, 0.5)
+        } else {
+            Color::WHITE
+        };
+
+        if sprite.color != color {
+            sprite.color = color;
         }
     }
 }
@@ -311,10 +1389,11 @@ fn is_point_in_play_area(point: Vec2, play_area_radius: f32, play_area_position:
 
 pub fn select_card(
     mut commands: Commands,
+    current_player: Single<&CurrentPlayer>,
     camera_query: Single<(&Camera, &GlobalTransform)>,
     windows: Query<&Window>,
     buttons: Res<ButtonInput<MouseButton>>,
-    card_query: Query<(Entity, &Card, &Transform), Without<CardSelected>>,
+    card_query: Query<(Entity, &Card, &Transform, Option<&CardTween>), Without<CardSelected>>,
 ) {
     let point = match get_mouse_position(camera_query, windows) {
         Some(point) => point,
@@ -322,9 +1401,9 @@ pub fn select_card(
     };
 
     if buttons.just_pressed(MouseButton::Left) {
-        for (entity_id, _, transform) in card_query
+        for (entity_id, _, transform, tween) in card_query
             .iter()
-            .filter(|(_, card, _)| card.player_id.is_some())
+            .filter(|(_, card, _, _)| card.player_id == Some(current_player.0))
         {
             let mut entity = commands.entity(entity_id);
 
@@ -336,8 +1415,10 @@ pub fn select_card(
                 continue;
             }
 
-            entity.insert((CardSelected {
-                inital_card_position: (transform.translation.x, transform.translation.y),
+            // A card grabbed on its way into the hand goes back to where it was heading
+            let home = tween.map_or(transform.translation.truncate(), |tween| tween.end);
+            entity.remove::<CardTween>().insert((CardSelected {
+                inital_card_position: (home.x, home.y),
             },));
         }
     }
@@ -347,12 +1428,12 @@ pub fn unselect_card(
     mut commands: Commands,
     current_player: Single<&CurrentPlayer>,
     buttons: Res<ButtonInput<MouseButton>>,
-    mut card_query: Query<(Entity, &mut Transform, &CardSelected, &Card), With<CardSelected>>,
+    mut card_query: Query<(Entity, &Transform, &CardSelected, &Card), With<CardSelected>>,
     play_area_query: Query<(&PlayArea, &Transform), Without<CardSelected>>,
     mut play_events: EventWriter<CardPlayed>,
 ) {
     if buttons.just_released(MouseButton::Left) {
-        for (entity_id, mut transform, card_selected, card) in card_query.iter_mut() {
+        for (entity_id, transform, card_selected, card) in card_query.iter_mut() {
             let mut entity = commands.entity(entity_id);
             entity.remove::<CardSelected>();
 
@@ -363,14 +1444,23 @@ pub fn unselect_card(
                 play_area_transform.translation.y,
             );
 
-            if is_point_in_play_area(card_position, play_area.0, play_area_position) {
+            if card.player_id == Some(current_player.0)
+                && is_point_in_play_area(card_position, play_area.0, play_area_position)
+            {
                 play_events.send(CardPlayed {
                     player_id: current_player.0,
                     card: card.card,
                 });
             } else {
-                transform.translation.x = card_selected.inital_card_position.0;
-                transform.translation.y = card_selected.inital_card_position.1;
+                entity.insert(card_tween(
+                    card_position,
+                    Vec2::new(
+                        card_selected.inital_card_position.0,
+                        card_selected.inital_card_position.1,
+                    ),
+                    0.0,
+                    CARD_MOVE_SECONDS,
+                ));
             }
         }
     }
@@ -393,6 +1483,128 @@ pub fn move_card(
     }
 }
 
+// The current player's hand from left to right, cards still sliding in count where they will land
+fn hand_in_order<'a>(
+    card_query: impl Iterator<Item = (Entity, &'a Card, &'a Transform, Option<&'a CardTween>)>,
+    player_id: usize,
+) -> Vec<(Entity, &'a Card, Vec2)> {
+    let mut hand: Vec<(Entity, &Card, Vec2)> = card_query
+        .filter(|(_, card, _, _)| card.player_id == Some(player_id))
+        .map(|(entity, card, transform, tween)| {
+            let position = tween.map_or(transform.translation.truncate(), |tween| tween.end);
+            (entity, card, position)
+        })
+        .collect();
+    hand.sort_by(|a, b| a.2.x.total_cmp(&b.2.x));
+    hand
+}
+
+// Keys only drive the table while no button has the focus and the seat is really being played
+fn keys_reach_table(
+    game_settings: &GameSettings,
+    player_id: usize,
+    button_focus: &ButtonFocus,
+    handoff_screen_query: &Query<Entity, With<HandoffScreen>>,
+) -> bool {
+    game_settings.seat_type(player_id) == SeatType::Human
+        && button_focus.focused.is_none()
+        && handoff_screen_query.is_empty()
+}
+
+// The arrows move along the hand and the confirm key plays the focused card
+pub fn handle_card_keys(
+    controls: ControlInput,
+    current_player: Single<&CurrentPlayer>,
+    game_settings: Res<GameSettings>,
+    button_focus: Res<ButtonFocus>,
+    handoff_screen_query: Query<Entity, With<HandoffScreen>>,
+    mut card_focus: ResMut<CardFocus>,
+    card_query: Query<(Entity, &Card, &Transform, Option<&CardTween>), Without<CardSelected>>,
+    mut play_events: EventWriter<CardPlayed>,
+) {
+    if !keys_reach_table(
+        &game_settings,
+        current_player.0,
+        &button_focus,
+        &handoff_screen_query,
+    ) {
+        return;
+    }
+
+    let hand = hand_in_order(card_query.iter(), current_player.0);
+    if hand.is_empty() {
+        return;
+    }
+
+    let last = hand.len() - 1;
+    if controls.just_pressed(ControlAction::NextCard) {
+        card_focus.0 = Some(card_focus.0.map_or(0, |index| (index + 1).min(last)));
+    } else if controls.just_pressed(ControlAction::PreviousCard) {
+        card_focus.0 = Some(card_focus.0.map_or(last, |index| index.min(last).saturating_sub(1)));
+    } else if controls.just_pressed(ControlAction::Confirm)
+        && let Some(index) = card_focus.0
+    {
+        play_events.send(CardPlayed {
+            player_id: current_player.0,
+            card: hand[index.min(last)].1.card,
+        });
+    }
+}
+
+// A glow behind the focused card, it stays hidden until the keys are used
+pub fn highlight_focused_card(
+    mut commands: Commands,
+    current_player: Single<&CurrentPlayer>,
+    game_settings: Res<GameSettings>,
+    button_focus: Res<ButtonFocus>,
+    handoff_screen_query: Query<Entity, With<HandoffScreen>>,
+    card_focus: Res<CardFocus>,
+    card_query: Query<(Entity, &Card, &Transform, Option<&CardTween>), Without<CardSelected>>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<CardFocusHighlight>>,
+) {
+    let hand = hand_in_order(card_query.iter(), current_player.0);
+    let focused = card_focus
+        .0
+        .filter(|_| {
+            keys_reach_table(
+                &game_settings,
+                current_player.0,
+                &button_focus,
+                &handoff_screen_query,
+            )
+        })
+        .and_then(|index| hand.get(index.min(hand.len().saturating_sub(1))))
+        .and_then(|(entity, _, _)| card_query.get(*entity).ok())
+        .map(|(_, _, transform, _)| transform.translation);
+
+    let Ok((mut transform, mut visibility)) = highlight_query.get_single_mut() else {
+        if focused.is_some() {
+            commands.spawn((
+                Sprite::from_color(
+                    Color::from(YELLOW),
+                    Vec2::new(CARD_WIDTH + 12.0, CARD_HEIGHT + 12.0),
+                ),
+                Transform::from_xyz(0.0, 0.0, -1.0),
+                Visibility::Hidden,
+                CardFocusHighlight,
+                MatchUI,
+            ));
+        }
+        return;
+    };
+
+    match focused {
+        Some(position) => {
+            // Just behind the card, so it shows as a border
+            transform.translation = position.with_z(position.z - 1.0);
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+        None => {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+}
+
 fn get_mouse_position(
     camera_query: Single<(&Camera, &GlobalTransform)>,
     windows: Query<&Window>,
@@ -421,9 +1633,15 @@ pub fn handle_guess_action(
         (Changed<Interaction>, With<Button>),
     >,
     mut guess: Single<&mut Guess>,
+    max_guess: Single<&MaxGuess>,
     current_player: Single<&CurrentPlayer>,
     mut player_guessed_events: EventWriter<PlayerGuessed>,
+    game_settings: Res<GameSettings>,
 ) {
+    if game_settings.seat_type(current_player.0) != SeatType::Human {
+        return;
+    }
+
     for (interaction, button_action, disabled) in &interaction_query {
         if *interaction == Interaction::Pressed && disabled.is_none() {
             match button_action {
@@ -433,8 +1651,7 @@ pub fn handle_guess_action(
                     }
                 }
                 MatchButtonAction::AddGuess => {
-                    if guess.0 < 3 {
-                        //TODO: Change this to inital card count
+                    if guess.0 < max_guess.0 {
                         guess.0 += 1;
                     }
                 }
@@ -449,6 +1666,40 @@ pub fn handle_guess_action(
     }
 }
 
+// Number keys pick the guess, the up and down keys change it and the confirm key places it
+pub fn handle_guess_keys(
+    controls: ControlInput,
+    current_player: Single<&CurrentPlayer>,
+    game_settings: Res<GameSettings>,
+    button_focus: Res<ButtonFocus>,
+    handoff_screen_query: Query<Entity, With<HandoffScreen>>,
+    max_guess: Single<&MaxGuess>,
+    mut guess: Single<&mut Guess>,
+    mut player_guessed_events: EventWriter<PlayerGuessed>,
+) {
+    if !keys_reach_table(
+        &game_settings,
+        current_player.0,
+        &button_focus,
+        &handoff_screen_query,
+    ) {
+        return;
+    }
+
+    if let Some(digit) = controls.just_pressed_digit() {
+        guess.0 = digit.min(max_guess.0);
+    } else if controls.just_pressed(ControlAction::GuessUp) && guess.0 < max_guess.0 {
+        guess.0 += 1;
+    } else if controls.just_pressed(ControlAction::GuessDown) && guess.0 > 0 {
+        guess.0 -= 1;
+    } else if controls.just_pressed(ControlAction::Confirm) {
+        player_guessed_events.send(PlayerGuessed {
+            player_id: current_player.0,
+            guess: guess.0,
+        });
+    }
+}
+
 pub fn handle_guess_changed(mut guess_changed_query: Query<(&Guess, &mut Text), Changed<Guess>>) {
     for (guess, mut text) in guess_changed_query.iter_mut() {
         text.0 = format!("What's your guess: {}", guess.0);
@@ -457,16 +1708,21 @@ pub fn handle_guess_changed(mut guess_changed_query: Query<(&Guess, &mut Text),
 
 pub fn enable_disable_add_guess_button(
     mut commands: Commands,
-    max_guess: Single<&MaxGuess>,
-    guess_count_query: Query<&Guess, Changed<Guess>>,
+    max_guess: Single<Ref<MaxGuess>>,
+    guess_count_query: Query<Ref<Guess>>,
     mut add_player_button_query: Query<
         (Entity, Option<&ButtonDisabled>, &AddGuessButton),
         With<Button>,
     >,
 ) {
+    let max_guess_changed = max_guess.is_changed();
     for guess_count in guess_count_query.iter() {
+        if !guess_count.is_changed() && !max_guess_changed {
+            continue;
+        }
+
         for (entity, disabled, _) in &mut add_player_button_query {
-            if guess_count.0 == max_guess.0 && disabled.is_none() {
+            if guess_count.0 >= max_guess.0 && disabled.is_none() {
                 commands.entity(entity).insert(ButtonDisabled);
             } else if guess_count.0 < max_guess.0 && disabled.is_some() {
                 commands.entity(entity).remove::<ButtonDisabled>();
@@ -496,9 +1752,10 @@ pub fn enable_disable_remove_guess_button(
 
 pub fn handle_current_player_changed(
     mut current_player_query: Query<(&CurrentPlayer, &mut Text), Changed<CurrentPlayer>>,
+    game_settings: Res<GameSettings>,
 ) {
     for (current_player, mut text) in current_player_query.iter_mut() {
-        text.0 = format!("Player {}'s turn", current_player.0 + 1);
+        text.0 = format!("{}'s turn", game_settings.player_name(current_player.0));
     }
 }
 
@@ -515,23 +1772,56 @@ pub fn setup_play_area(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    windows: Query<&Window>,
+    table_layout: Res<TableLayout>,
     existing_play_area: Option<Single<&PlayArea>>,
 ) {
     if existing_play_area.is_some() {
         return;
     }
 
-    let window = windows.single();
-    let height = window.height();
+    let radius = table_layout.play_area_radius();
+    let center = table_layout.center();
+    commands.spawn((
+        PlayAreaBundle {
+            mesh: Mesh2d(meshes.add(Annulus::new(radius - 2.0, radius))),
+            mesh_material: MeshMaterial2d(materials.add(Color::from(CRIMSON))),
+            transform: Transform::from_xyz(center.x, center.y, 20.0),
+            play_area: PlayArea(radius),
+        },
+        MatchUI,
+    ));
+}
 
-    const RADIUS: f32 = 180.0;
-    commands.spawn(PlayAreaBundle {
-        mesh: Mesh2d(meshes.add(Annulus::new(RADIUS - 2.0, RADIUS))),
-        mesh_material: MeshMaterial2d(materials.add(Color::from(CRIMSON))),
-        transform: Transform::from_xyz(0.0, 0.0 + height / 4.0, 20.0),
-        play_area: PlayArea(RADIUS),
-    });
+// The ring is rebuilt rather than scaled, so its outline keeps the same width
+pub fn resize_play_area(
+    mut meshes: ResMut<Assets<Mesh>>,
+    table_layout: Res<TableLayout>,
+    mut play_area_query: Query<(&mut PlayArea, &mut Mesh2d, &mut Transform)>,
+) {
+    if !table_layout.is_changed() {
+        return;
+    }
+
+    let radius = table_layout.play_area_radius();
+    let center = table_layout.center();
+    for (mut play_area, mut mesh, mut transform) in play_area_query.iter_mut() {
+        play_area.0 = radius;
+        mesh.0 = meshes.add(Annulus::new(radius - 2.0, radius));
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
+
+pub fn update_table_layout(windows: Query<&Window>, mut table_layout: ResMut<TableLayout>) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    // The window also changes when the cursor moves, so only a new size counts
+    let size = Vec2::new(window.width(), window.height());
+    if table_layout.size != size {
+        table_layout.size = size;
+    }
 }
 
 pub fn highlight_play_area(
@@ -582,64 +1872,373 @@ pub fn adjust_top_played_card(
     }
 }
 
-pub fn player_info_ui_setup(
+// Every seat shows its name and scores, opponents also show how many cards they hold
+pub fn display_seats(
     mut commands: Commands,
-    player_info_query: Query<&PlayerInfo, Added<PlayerInfo>>,
+    game_logic: Res<GameLogicRes>,
+    game_settings: Res<GameSettings>,
+    table_layout: Res<TableLayout>,
+    asset_server: Res<AssetServer>,
+    current_player: Single<Ref<CurrentPlayer>>,
+    player_info_query: Query<Ref<PlayerInfo>>,
+    mut seat_query: Query<(Entity, &SeatDisplay, &mut Transform)>,
+    mut seats_viewer: Local<Option<usize>>,
 ) {
-    if player_info_query.is_empty() {
-        return;
+    let player_count = game_logic.0.get_player_count();
+    let infos_changed = player_info_query.iter().any(|info| info.is_changed());
+    if !infos_changed && !current_player.is_changed() {
+        if !table_layout.is_changed() {
+            return;
+        }
+
+        // A resize only moves the seats that are already there, a new viewer changes what they show
+        if !seat_query.is_empty() && *seats_viewer == Some(table_layout.viewer) {
+            for (_, seat, mut transform) in seat_query.iter_mut() {
+                let position = table_layout.seat_position(seat.0, player_count);
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+            return;
+        }
     }
 
-    let mut entity = commands.spawn((
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(12.0),
-            right: Val::Px(12.0),
-            flex_direction: FlexDirection::Column,
-            ..default()
-        },
-        MatchUI,
-    ));
+    for (entity, _, _) in seat_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *seats_viewer = Some(table_layout.viewer);
 
-    entity.with_children(|parent| {
-        for player_info in player_info_query.iter() {
-            parent.spawn((
-                Text::new(format!(
-                    "Player {}: Cards: {} | Guess: {} | Wins: {}",
-                    player_info.player_id + 1,
-                    player_info.card_count,
-                    player_info.guess,
-                    player_info.wins,
-                )),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(TEXT_COLOR),
-                Node {
-                    margin: UiRect::all(Val::Px(5.0)),
-                    ..default()
-                },
-                PlayerInfoUI(player_info.player_id),
-            ));
+    let view = game_logic.0.get_player_view(table_layout.viewer);
+    const BACK_SCALE: f32 = 0.3;
+    const MAX_BACKS: usize = 5;
+
+    for player_info in player_info_query.iter() {
+        let player_id = player_info.player_id;
+        let position = table_layout.seat_position(player_id, player_count);
+        let is_turn = player_id == current_player.0;
+        // The viewer's own hand and hands visible in the blind round are drawn face up
+        let hidden_cards = match view.seats.get(player_id) {
+            Some(seat) if player_id != table_layout.viewer && seat.hand.is_none() => {
+                seat.hand_size
+            }
+            _ => 0,
+        };
+
+        commands
+            .spawn((
+                Transform::from_xyz(position.x, position.y, 5.0),
+                Visibility::default(),
+                SeatDisplay(player_id),
+                MatchUI,
+            ))
+            .with_children(|parent| {
+                if is_turn {
+                    parent.spawn((
+                        Sprite::from_color(
+                            Color::srgba(1.0, 1.0, 0.0, 0.2),
+                            Vec2::new(240.0, 60.0),
+                        ),
+                        Transform::from_xyz(0.0, 0.0, -1.0),
+                    ));
+                }
+
+                parent.spawn((
+                    Text2d::new(player_info_text(player_info.as_ref(), &game_settings)),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(if is_turn {
+                        Color::from(YELLOW)
+                    } else {
+                        TEXT_COLOR
+                    }),
+                ));
+
+                let shown_backs = hidden_cards.min(MAX_BACKS);
+                for index in 0..shown_backs {
+                    let offset = (index as f32 - (shown_backs as f32 - 1.0) / 2.0) * 14.0;
+                    parent.spawn((
+                        asset_server.card_back_sprite(Vec2::new(
+                            CARD_WIDTH * BACK_SCALE,
+                            CARD_HEIGHT * BACK_SCALE,
+                        )),
+                        Transform::from_xyz(
+                            offset,
+                            CARD_HEIGHT * BACK_SCALE * 0.5 + 30.0,
+                            index as f32 * 0.01,
+                        ),
+                    ));
+                }
+            });
+    }
+}
+
+fn player_info_text(player_info: &PlayerInfo, game_settings: &GameSettings) -> String {
+    let player_name = game_settings.player_name(player_info.player_id);
+
+    if player_info.lives == 0 {
+        return format!("{}\nEliminated", player_name);
+    }
+
+    format!(
+        "{}\nLives: {} | Cards: {} | Guess: {} | Wins: {}",
+        player_name,
+        player_info.lives,
+        player_info.card_count,
+        player_info.guess,
+        player_info.wins,
+    )
+}
+
+pub fn announce_eliminations(
+    mut commands: Commands,
+    mut player_eliminated_events: EventReader<PlayerEliminated>,
+    game_settings: Res<GameSettings>,
+) {
+    for event in player_eliminated_events.read() {
+        commands.spawn((
+            Text::new(format!(
+                "{} was eliminated",
+                game_settings.player_name(event.player_id)
+            )),
+            TextFont {
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::from(CRIMSON)),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(80.0 + 40.0 * event.player_id as f32),
+                left: Val::Percent(40.0),
+                ..default()
+            },
+            EliminationNotice(Timer::from_seconds(3.0, TimerMode::Once)),
+            MatchUI,
+        ));
+    }
+}
+
+pub fn clear_elimination_notices(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notice_query: Query<(Entity, &mut EliminationNotice)>,
+) {
+    for (entity_id, mut notice) in notice_query.iter_mut() {
+        if notice.0.tick(time.delta()).just_finished() {
+            commands.entity(entity_id).despawn_recursive();
+        }
+    }
+}
+
+pub fn outcome_text(outcome: &GameOutcome, game_settings: &GameSettings) -> String {
+    match outcome {
+        GameOutcome::Winner(player_id) => {
+            format!("{} wins!", game_settings.player_name(*player_id))
+        }
+        GameOutcome::Draw(player_ids) => {
+            let players: Vec<String> = player_ids
+                .iter()
+                .map(|player_id| game_settings.player_name(*player_id))
+                .collect();
+            format!("Draw between {}", players.join(", "))
         }
+        GameOutcome::Abandoned => "Game abandoned".to_string(),
+    }
+}
+
+pub fn game_over_setup(
+    mut commands: Commands,
+    game_logic: Res<GameLogicRes>,
+    game_state: Res<State<GameState>>,
+    game_settings: Res<GameSettings>,
+    mut game_ended_events: EventReader<GameEnded>,
+) {
+    let outcome = game_ended_events
+        .read()
+        .last()
+        .map(|event| event.outcome.clone())
+        .or_else(|| game_logic.0.get_outcome())
+        .unwrap_or(GameOutcome::Abandoned);
+
+    // Players still in the game rank first, then the others by the last match they played, so
+    // later eliminations rank higher. Tricks won break the ties
+    let match_history = game_logic.0.get_match_history();
+    let player_count = game_logic.0.get_player_count();
+    let mut standings: Vec<(usize, usize, Option<usize>, PlayerStats)> = (0..player_count)
+        .map(|player_id| {
+            (
+                player_id,
+                game_logic.0.get_player_lives(player_id),
+                match_history
+                    .iter()
+                    .rposition(|record| record.played(player_id)),
+                game_logic.0.get_player_stats(player_id),
+            )
+        })
+        .collect();
+    standings.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then(b.2.cmp(&a.2))
+            .then(b.3.tricks_won.cmp(&a.3.tricks_won))
     });
+
+    let button_node = Node {
+        width: Val::Px(250.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(20.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let button_text_font = TextFont {
+        font_size: 25.0,
+        ..default()
+    };
+
+    let cell_font = TextFont {
+        font_size: 20.0,
+        ..default()
+    };
+
+    let mut buttons = vec![("Back to menu", GameOverButtonAction::BackToMenu)];
+    // Online rooms are started again by their host
+    if *game_state.get() == GameState::LocalGame {
+        buttons.insert(0, ("Play again", GameOverButtonAction::PlayAgain));
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnGameOverScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(outcome_text(&outcome, &game_settings)),
+                        TextFont {
+                            font_size: 50.0,
+                            ..default()
+                        },
+                        TextColor(Color::from(YELLOW)),
+                        Node {
+                            margin: UiRect::all(Val::Px(30.0)),
+                            ..default()
+                        },
+                    ));
+
+                    let header = [
+                        "#".to_string(),
+                        "Player".to_string(),
+                        "Lives".to_string(),
+                        "Guesses hit".to_string(),
+                        "Tricks won".to_string(),
+                        "Lives lost / match".to_string(),
+                    ];
+                    let rows = standings
+                        .iter()
+                        .enumerate()
+                        .map(|(place, (player_id, lives, _, stats))| {
+                            [
+                                (place + 1).to_string(),
+                                game_settings.player_name(*player_id),
+                                lives.to_string(),
+                                format!(
+                                    "{}/{} ({:.0}%)",
+                                    stats.guesses_hit,
+                                    stats.matches_played,
+                                    stats.guess_hit_rate() * 100.0
+                                ),
+                                stats.tricks_won.to_string(),
+                                format!("{:.1}", stats.lives_lost_per_match()),
+                            ]
+                        });
+
+                    for row in std::iter::once(header).chain(rows) {
+                        parent
+                            .spawn(Node {
+                                flex_direction: FlexDirection::Row,
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for cell in row {
+                                    parent.spawn((
+                                        Text::new(cell),
+                                        cell_font.clone(),
+                                        TextColor(TEXT_COLOR),
+                                        Node {
+                                            width: Val::Px(170.0),
+                                            margin: UiRect::all(Val::Px(4.0)),
+                                            ..default()
+                                        },
+                                    ));
+                                }
+                            });
+                    }
+
+                    parent
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Row,
+                            margin: UiRect::top(Val::Px(20.0)),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (label, action) in buttons {
+                                parent
+                                    .spawn((
+                                        Button,
+                                        button_node.clone(),
+                                        BackgroundColor(NORMAL_BUTTON),
+                                        action,
+                                    ))
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            Text::new(label),
+                                            button_text_font.clone(),
+                                            TextColor(TEXT_COLOR),
+                                        ));
+                                    });
+                            }
+                        });
+                });
+        });
 }
 
-pub fn player_info_ui_update(
-    player_info_query: Query<&PlayerInfo, Changed<PlayerInfo>>,
-    mut player_info_ui_query: Query<(&PlayerInfoUI, &mut Text)>,
+// Leaving the game state tears the match down, so both buttons start from a clean slate
+pub fn handle_game_over_action(
+    interaction_query: Query<
+        (&Interaction, &GameOverButtonAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    current_game_state: Res<State<GameState>>,
+    mut game_state: ResMut<NextState<GameState>>,
 ) {
-    for player_info in player_info_query.iter() {
-        for (player_info_ui, mut text) in player_info_ui_query.iter_mut() {
-            if player_info_ui.0 == player_info.player_id {
-                text.0 = format!(
-                    "Player {}: Cards: {} | Guess: {} | Wins: {}",
-                    player_info.player_id + 1,
-                    player_info.card_count,
-                    player_info.guess,
-                    player_info.wins,
-                )
+    for (interaction, button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match button_action {
+                // Only a local game can be dealt again here, online rooms wait for their host
+                GameOverButtonAction::PlayAgain => {
+                    if *current_game_state.get() == GameState::LocalGame {
+                        game_state.set(GameState::LocalGameInit);
+                    }
+                }
+                GameOverButtonAction::BackToMenu => game_state.set(GameState::Menu),
             }
         }
     }
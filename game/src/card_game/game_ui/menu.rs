@@ -5,10 +5,13 @@ pub mod systems;
 use bevy::prelude::*;
 use events::{AddPlayer, RemovePlayer};
 use systems::{
-    add_player, cleanup_local_game_menu, cleanup_main_menu, cleanup_online_game_menu,
-    enable_disable_add_player_button, enable_disable_remove_player_button, local_game_menu_setup,
-    main_menu_setup, menu_action, menu_setup, online_game_menu_setup, remove_player,
-    update_player_count_text,
+    add_player, apply_custom_deck_input, apply_settings_inputs, apply_settings_inputs_on_exit,
+    cleanup_local_game_menu, cleanup_main_menu, cleanup_online_game_menu, confirm_local_game,
+    confirm_online_game, enable_disable_add_player_button, enable_disable_remove_player_button,
+    local_game_menu_setup, main_menu_setup, menu_action, menu_setup, online_game_menu_setup,
+    remove_player, settings_menu_setup, submit_online_form, update_custom_deck_input,
+    update_handoff_toggle_text, update_player_count_text, update_rule_option_texts,
+    update_seat_type_buttons, update_setting_option_texts,
 };
 
 use crate::card_game::GameState;
@@ -20,6 +23,7 @@ pub enum MenuState {
     Main,
     LocalGame,
     OnlineGame,
+    Settings,
     #[default]
     Disabled,
 }
@@ -37,15 +41,34 @@ impl Plugin for GameUIMenuPlugin {
             .add_systems(OnExit(MenuState::LocalGame), cleanup_local_game_menu)
             .add_systems(OnEnter(MenuState::OnlineGame), online_game_menu_setup)
             .add_systems(OnExit(MenuState::OnlineGame), cleanup_online_game_menu)
+            .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
+            .add_systems(
+                OnExit(MenuState::Settings),
+                (
+                    apply_settings_inputs_on_exit,
+                    despawn_screen::<components::OnSettingsScreen>,
+                )
+                    .chain(),
+            )
             .add_event::<AddPlayer>()
             .add_event::<RemovePlayer>()
             .add_systems(
                 Update,
                 (
+                    submit_online_form.before(confirm_online_game),
                     menu_action,
+                    confirm_local_game,
+                    confirm_online_game,
                     add_player,
                     remove_player,
                     update_player_count_text,
+                    update_seat_type_buttons,
+                    update_handoff_toggle_text,
+                    update_rule_option_texts,
+                    apply_custom_deck_input.before(update_custom_deck_input),
+                    update_custom_deck_input,
+                    update_setting_option_texts,
+                    apply_settings_inputs,
                     enable_disable_add_player_button,
                     enable_disable_remove_player_button,
                 )
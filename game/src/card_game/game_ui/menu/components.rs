@@ -9,14 +9,22 @@ pub struct OnLocalGameScreen;
 #[derive(Component)]
 pub struct OnOnlineGameScreen;
 
+#[derive(Component)]
+pub struct OnSettingsScreen;
+
 #[derive(Component)]
 pub enum MenuButtonAction {
     PlayLocalGame,
     ConfirmLocalGame,
     RemoveLocalPlayer,
     AddLocalPlayer,
+    CycleSeatType(usize),
+    CycleRule(RuleOption),
+    ToggleHandoff,
     PlayOnlineGame,
     ConfirmOnlineGame,
+    Settings,
+    CycleSetting(SettingOption),
     BackToMainMenu,
     Quit,
 }
@@ -30,8 +38,65 @@ pub struct AddPlayerButton;
 #[derive(Component)]
 pub struct RemovePlayerButton;
 
+#[derive(Component)]
+pub struct SeatTypeButton(pub usize);
+
+#[derive(Component)]
+pub struct HandoffToggleText;
+
+// Holds the name input and the seat type button of one seat
+#[derive(Component)]
+pub struct SeatRow(pub usize);
+
+#[derive(Component)]
+pub struct SeatNameInput(pub usize);
+
+#[derive(Component)]
+pub struct SeedInput;
+
+// Shows the rank order of the chosen deck and takes a custom one
+#[derive(Component)]
+pub struct CustomDeckInput;
+
+#[derive(Component)]
+pub struct SetupErrorText;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RuleOption {
+    CardCount,
+    StartingLives,
+    Trump,
+    Tie,
+    BlindRound,
+    Schedule,
+    LifeLoss,
+    Deck,
+    DeckCount,
+    Undo,
+}
+
+#[derive(Component)]
+pub struct RuleOptionText(pub RuleOption);
+
 #[derive(Component)]
 pub struct RoomNameInput;
 
 #[derive(Component)]
-pub struct PlayerNameInput;
\ No newline at end of file
+pub struct PlayerNameInput;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingOption {
+    WindowSize,
+    Fullscreen,
+    VSync,
+    AnimationSpeed,
+    BotSpeed,
+}
+
+#[derive(Component)]
+pub struct SettingOptionText(pub SettingOption);
+
+#[derive(Component)]
+pub struct DefaultNameInput;
+
+#[derive(Component)]
+pub struct ServerAddressInput;
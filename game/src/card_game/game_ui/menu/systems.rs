@@ -1,15 +1,53 @@
-use bevy::color::palettes::css::CRIMSON;
+use bevy::color::palettes::css::{CRIMSON, YELLOW};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use card_game_logic::bot::{BotDifficulty, SeatType};
+use card_game_logic::game_logic::common::DeckSpec;
+use card_game_logic::game_logic::rules::{
+    CancelledTrick, LifeLoss, RoundSchedule, TieRule, TrumpRule, UndoRule,
+};
 
 use crate::card_game::game_ui::components::ButtonDisabled;
+use crate::card_game::game_ui::controls::resources::ButtonFocus;
 use crate::card_game::game_ui::ui_entities::text_input::TextInputSpawner;
-use crate::card_game::game_ui::ui_entities::text_input::components::TextInput;
+use crate::card_game::game_ui::ui_entities::text_input::components::{
+    TextInput, TextInputActive, TextInputFilter, TextInputPlaceholder, TextInputValidator,
+};
+use crate::card_game::game_ui::ui_entities::text_input::events::TextInputSubmitted;
+use crate::card_game::preferences::{
+    ANIMATION_SPEEDS, BOT_SPEEDS, Preferences, WINDOW_SIZES, animation_speed_text, bot_speed_text,
+    next_in, parse_server_address,
+};
 use crate::card_game::{GameSettings, GameState};
 
 const MIN_PLAYERS: usize = 2;
 const MAX_PLAYERS: usize = 8;
+const MAX_CARD_COUNT: usize = 10;
+const MAX_STARTING_LIVES: usize = 10;
+const MAX_DECK_COUNT: usize = 4;
 
-use super::super::{DISABLED_BUTTON, NORMAL_BUTTON, TEXT_COLOR};
+const SETTING_OPTIONS: [SettingOption; 5] = [
+    SettingOption::WindowSize,
+    SettingOption::Fullscreen,
+    SettingOption::VSync,
+    SettingOption::AnimationSpeed,
+    SettingOption::BotSpeed,
+];
+
+const RULE_OPTIONS: [RuleOption; 10] = [
+    RuleOption::CardCount,
+    RuleOption::StartingLives,
+    RuleOption::Trump,
+    RuleOption::Tie,
+    RuleOption::BlindRound,
+    RuleOption::Schedule,
+    RuleOption::LifeLoss,
+    RuleOption::Deck,
+    RuleOption::DeckCount,
+    RuleOption::Undo,
+];
+
+use super::super::{NORMAL_BUTTON, TEXT_COLOR};
 
 use super::MenuState;
 use super::components::*;
@@ -100,6 +138,21 @@ pub fn main_menu_setup(mut commands: Commands) {
                             ));
                         });
 
+                    parent
+                        .spawn((
+                            Button,
+                            button_node.clone(),
+                            BackgroundColor(NORMAL_BUTTON),
+                            MenuButtonAction::Settings,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new("Settings"),
+                                button_text_font.clone(),
+                                TextColor(TEXT_COLOR),
+                            ));
+                        });
+
                     parent
                         .spawn((
                             Button,
@@ -118,12 +171,21 @@ pub fn main_menu_setup(mut commands: Commands) {
         });
 }
 
-pub fn local_game_menu_setup(mut commands: Commands) {
+pub fn local_game_menu_setup(mut commands: Commands, game_settings: Res<GameSettings>) {
     // Common style for all buttons on the screen
     let button_node = Node {
         width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
+        height: Val::Px(55.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let option_button_node = Node {
+        width: Val::Px(360.0),
+        height: Val::Px(40.0),
+        margin: UiRect::all(Val::Px(4.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
         ..default()
@@ -134,6 +196,13 @@ pub fn local_game_menu_setup(mut commands: Commands) {
         ..default()
     };
 
+    let option_text_font = TextFont {
+        font_size: 20.0,
+        ..default()
+    };
+
+    let player_count = game_settings.player_count.clamp(MIN_PLAYERS, MAX_PLAYERS);
+
     commands
         .spawn((
             Node {
@@ -151,44 +220,267 @@ pub fn local_game_menu_setup(mut commands: Commands) {
                     Node {
                         flex_direction: FlexDirection::Column,
                         align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(10.0)),
                         ..default()
                     },
                     BackgroundColor(CRIMSON.into()),
                 ))
                 .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Number of Players: 2"),
-                        TextFont {
-                            font_size: 33.0,
-                            ..default()
-                        },
-                        TextColor(TEXT_COLOR),
-                        Node {
-                            margin: UiRect::all(Val::Px(50.0)),
+                    parent
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Start,
                             ..default()
-                        },
-                        NumberOfLocalPLayers(2),
+                        })
+                        .with_children(|parent| {
+                            // Left column: who is playing
+                            parent
+                                .spawn(Node {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    margin: UiRect::horizontal(Val::Px(10.0)),
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        Text::new(format!("Number of Players: {}", player_count)),
+                                        TextFont {
+                                            font_size: 33.0,
+                                            ..default()
+                                        },
+                                        TextColor(TEXT_COLOR),
+                                        Node {
+                                            margin: UiRect::all(Val::Px(10.0)),
+                                            ..default()
+                                        },
+                                        NumberOfLocalPLayers(player_count),
+                                    ));
+
+                                    parent
+                                        .spawn((Node {
+                                            flex_direction: FlexDirection::Row,
+                                            align_items: AlignItems::Center,
+                                            ..default()
+                                        },))
+                                        .with_children(|parent| {
+                                            parent
+                                                .spawn((
+                                                    Button,
+                                                    Node {
+                                                        width: Val::Px(200.0),
+                                                        ..button_node.clone()
+                                                    },
+                                                    BackgroundColor(NORMAL_BUTTON),
+                                                    MenuButtonAction::RemoveLocalPlayer,
+                                                    RemovePlayerButton,
+                                                ))
+                                                .with_children(|parent| {
+                                                    parent.spawn((
+                                                        Text::new("Remove Player"),
+                                                        button_text_font.clone(),
+                                                        TextColor(TEXT_COLOR),
+                                                    ));
+                                                });
+
+                                            parent
+                                                .spawn((
+                                                    Button,
+                                                    Node {
+                                                        width: Val::Px(200.0),
+                                                        ..button_node.clone()
+                                                    },
+                                                    BackgroundColor(NORMAL_BUTTON),
+                                                    MenuButtonAction::AddLocalPlayer,
+                                                    AddPlayerButton,
+                                                ))
+                                                .with_children(|parent| {
+                                                    parent.spawn((
+                                                        Text::new("Add Player"),
+                                                        button_text_font.clone(),
+                                                        TextColor(TEXT_COLOR),
+                                                    ));
+                                                });
+                                        });
+
+                                    for player_id in 0..MAX_PLAYERS {
+                                        parent
+                                            .spawn((
+                                                Node {
+                                                    flex_direction: FlexDirection::Row,
+                                                    align_items: AlignItems::Center,
+                                                    ..default()
+                                                },
+                                                SeatRow(player_id),
+                                            ))
+                                            .with_children(|parent| {
+                                                let name = game_settings
+                                                    .player_names
+                                                    .get(player_id)
+                                                    .cloned()
+                                                    .unwrap_or_default();
+
+                                                parent
+                                                    .spawn_text_input(
+                                                        "Name:", &name, 16, 20.0, 260.0, 40.0,
+                                                    )
+                                                    .insert((
+                                                        SeatNameInput(player_id),
+                                                        TextInputPlaceholder(format!(
+                                                            "Player {}",
+                                                            player_id + 1
+                                                        )),
+                                                    ))
+                                                    .entry::<Node>()
+                                                    .and_modify(compact_text_input);
+
+                                                parent
+                                                    .spawn((
+                                                        Button,
+                                                        Node {
+                                                            width: Val::Px(200.0),
+                                                            ..option_button_node.clone()
+                                                        },
+                                                        BackgroundColor(NORMAL_BUTTON),
+                                                        MenuButtonAction::CycleSeatType(player_id),
+                                                        SeatTypeButton(player_id),
+                                                    ))
+                                                    .with_children(|parent| {
+                                                        parent.spawn((
+                                                            Text::new(seat_type_label(
+                                                                game_settings.seat_type(player_id),
+                                                            )),
+                                                            option_text_font.clone(),
+                                                            TextColor(TEXT_COLOR),
+                                                        ));
+                                                    });
+                                            });
+                                    }
+                                });
+
+                            // Right column: how the game is played
+                            parent
+                                .spawn(Node {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    margin: UiRect::horizontal(Val::Px(10.0)),
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        Text::new("Rules"),
+                                        TextFont {
+                                            font_size: 33.0,
+                                            ..default()
+                                        },
+                                        TextColor(TEXT_COLOR),
+                                        Node {
+                                            margin: UiRect::all(Val::Px(10.0)),
+                                            ..default()
+                                        },
+                                    ));
+
+                                    for option in RULE_OPTIONS {
+                                        parent
+                                            .spawn((
+                                                Button,
+                                                option_button_node.clone(),
+                                                BackgroundColor(NORMAL_BUTTON),
+                                                MenuButtonAction::CycleRule(option),
+                                            ))
+                                            .with_children(|parent| {
+                                                parent.spawn((
+                                                    Text::new(rule_option_label(
+                                                        option,
+                                                        &game_settings,
+                                                    )),
+                                                    option_text_font.clone(),
+                                                    TextColor(TEXT_COLOR),
+                                                    RuleOptionText(option),
+                                                ));
+                                            });
+                                    }
+
+                                    parent
+                                        .spawn((
+                                            Button,
+                                            option_button_node.clone(),
+                                            BackgroundColor(NORMAL_BUTTON),
+                                            MenuButtonAction::ToggleHandoff,
+                                        ))
+                                        .with_children(|parent| {
+                                            parent.spawn((
+                                                Text::new(handoff_label(
+                                                    game_settings.hot_seat_handoff,
+                                                )),
+                                                option_text_font.clone(),
+                                                TextColor(TEXT_COLOR),
+                                                HandoffToggleText,
+                                            ));
+                                        });
+
+                                    parent
+                                        .spawn_text_input(
+                                            "Ranks:",
+                                            &game_settings.rules.deck.rank_list(),
+                                            40,
+                                            20.0,
+                                            360.0,
+                                            40.0,
+                                        )
+                                        .insert((
+                                            CustomDeckInput,
+                                            TextInputFilter(is_rank_list_character),
+                                            TextInputValidator(validate_rank_list),
+                                        ))
+                                        .entry::<Node>()
+                                        .and_modify(compact_text_input);
+
+                                    let seed = game_settings
+                                        .seed
+                                        .map(|seed| seed.to_string())
+                                        .unwrap_or_default();
+                                    parent
+                                        .spawn_text_input("Seed:", &seed, 18, 20.0, 360.0, 40.0)
+                                        .insert((
+                                            SeedInput,
+                                            TextInputPlaceholder("Random".to_string()),
+                                            TextInputFilter(|character| character.is_ascii_digit()),
+                                            TextInputValidator(validate_seed),
+                                        ))
+                                        .entry::<Node>()
+                                        .and_modify(compact_text_input);
+
+                                    parent.spawn((
+                                        Text::new("Leave the seed empty for random deals"),
+                                        option_text_font.clone(),
+                                        TextColor(TEXT_COLOR),
+                                    ));
+                                });
+                        });
+
+                    parent.spawn((
+                        Text::new(""),
+                        option_text_font.clone(),
+                        TextColor(Color::from(YELLOW)),
+                        SetupErrorText,
                     ));
 
                     parent
-                        .spawn((Node {
+                        .spawn(Node {
                             flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
                             ..default()
-                        },))
+                        })
                         .with_children(|parent| {
                             parent
                                 .spawn((
                                     Button,
                                     button_node.clone(),
-                                    BackgroundColor(DISABLED_BUTTON),
-                                    MenuButtonAction::RemoveLocalPlayer,
-                                    ButtonDisabled,
-                                    RemovePlayerButton,
+                                    BackgroundColor(NORMAL_BUTTON),
+                                    MenuButtonAction::ConfirmLocalGame,
                                 ))
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("Remove Player"),
+                                        Text::new("Start Game"),
                                         button_text_font.clone(),
                                         TextColor(TEXT_COLOR),
                                     ));
@@ -199,28 +491,96 @@ pub fn local_game_menu_setup(mut commands: Commands) {
                                     Button,
                                     button_node.clone(),
                                     BackgroundColor(NORMAL_BUTTON),
-                                    MenuButtonAction::AddLocalPlayer,
-                                    AddPlayerButton,
+                                    MenuButtonAction::BackToMainMenu,
                                 ))
                                 .with_children(|parent| {
                                     parent.spawn((
-                                        Text::new("Add Player"),
+                                        Text::new("Back to Main Menu"),
                                         button_text_font.clone(),
                                         TextColor(TEXT_COLOR),
                                     ));
                                 });
                         });
+                });
+        });
+}
+
+pub fn online_game_menu_setup(mut commands: Commands, preferences: Res<Preferences>) {
+    // Common style for all buttons on the screen
+    let button_node = Node {
+        width: Val::Px(300.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(20.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let button_text_font = TextFont {
+        font_size: 25.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnOnlineGameScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(CRIMSON.into()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Online Game Setup"),
+                        TextFont {
+                            font_size: 33.0,
+                            ..default()
+                        },
+                        TextColor(TEXT_COLOR),
+                        Node {
+                            margin: UiRect::all(Val::Px(50.0)),
+                            ..default()
+                        },
+                    ));
+
+                    parent
+                        .spawn_text_input("Room name:", "", 20, 16.0, 500.0, 65.0)
+                        .insert((RoomNameInput, TextInputPlaceholder("lobby".to_string())));
+
+                    parent
+                        .spawn_text_input(
+                            "Player name:",
+                            &preferences.player_name,
+                            20,
+                            16.0,
+                            500.0,
+                            65.0,
+                        )
+                        .insert((PlayerNameInput, TextInputPlaceholder("Your name".to_string())));
 
                     parent
                         .spawn((
                             Button,
                             button_node.clone(),
                             BackgroundColor(NORMAL_BUTTON),
-                            MenuButtonAction::ConfirmLocalGame,
+                            MenuButtonAction::ConfirmOnlineGame,
                         ))
                         .with_children(|parent| {
                             parent.spawn((
-                                Text::new("Start Game"),
+                                Text::new("Join Game"),
                                 button_text_font.clone(),
                                 TextColor(TEXT_COLOR),
                             ));
@@ -244,12 +604,20 @@ pub fn local_game_menu_setup(mut commands: Commands) {
         });
 }
 
-pub fn online_game_menu_setup(mut commands: Commands) {
-    // Common style for all buttons on the screen
+pub fn settings_menu_setup(mut commands: Commands, preferences: Res<Preferences>) {
     let button_node = Node {
         width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
+        height: Val::Px(55.0),
+        margin: UiRect::all(Val::Px(10.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let option_button_node = Node {
+        width: Val::Px(400.0),
+        height: Val::Px(40.0),
+        margin: UiRect::all(Val::Px(4.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
         ..default()
@@ -260,6 +628,11 @@ pub fn online_game_menu_setup(mut commands: Commands) {
         ..default()
     };
 
+    let option_text_font = TextFont {
+        font_size: 20.0,
+        ..default()
+    };
+
     commands
         .spawn((
             Node {
@@ -269,7 +642,7 @@ pub fn online_game_menu_setup(mut commands: Commands) {
                 justify_content: JustifyContent::Center,
                 ..default()
             },
-            OnOnlineGameScreen,
+            OnSettingsScreen,
         ))
         .with_children(|parent| {
             parent
@@ -277,46 +650,77 @@ pub fn online_game_menu_setup(mut commands: Commands) {
                     Node {
                         flex_direction: FlexDirection::Column,
                         align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(10.0)),
                         ..default()
                     },
                     BackgroundColor(CRIMSON.into()),
                 ))
                 .with_children(|parent| {
                     parent.spawn((
-                        Text::new("Online Game Setup"),
+                        Text::new("Settings"),
                         TextFont {
                             font_size: 33.0,
                             ..default()
                         },
                         TextColor(TEXT_COLOR),
                         Node {
-                            margin: UiRect::all(Val::Px(50.0)),
+                            margin: UiRect::all(Val::Px(10.0)),
                             ..default()
                         },
                     ));
 
-                    parent
-                        .spawn_text_input("Room name:", "", 20, 16.0, 500.0, 65.0)
-                        .insert(RoomNameInput);
+                    // These take effect and are saved as soon as they are changed
+                    for option in SETTING_OPTIONS {
+                        parent
+                            .spawn((
+                                Button,
+                                option_button_node.clone(),
+                                BackgroundColor(NORMAL_BUTTON),
+                                MenuButtonAction::CycleSetting(option),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(setting_option_label(option, &preferences)),
+                                    option_text_font.clone(),
+                                    TextColor(TEXT_COLOR),
+                                    SettingOptionText(option),
+                                ));
+                            });
+                    }
 
+                    // The typed ones are taken once they are left, so half-typed values are never
+                    // used
                     parent
-                        .spawn_text_input("Player name:", "", 20, 16.0, 500.0, 65.0)
-                        .insert(PlayerNameInput);
+                        .spawn_text_input(
+                            "Default name:",
+                            &preferences.player_name,
+                            16,
+                            20.0,
+                            400.0,
+                            40.0,
+                        )
+                        .insert((DefaultNameInput, TextInputPlaceholder("Player 1".to_string())))
+                        .entry::<Node>()
+                        .and_modify(compact_text_input);
 
                     parent
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(NORMAL_BUTTON),
-                            MenuButtonAction::ConfirmOnlineGame,
+                        .spawn_text_input(
+                            "Server:",
+                            &preferences.server_address.to_string(),
+                            21,
+                            20.0,
+                            400.0,
+                            40.0,
+                        )
+                        .insert((
+                            ServerAddressInput,
+                            TextInputFilter(|character| {
+                                character.is_ascii_digit() || character == '.' || character == ':'
+                            }),
+                            TextInputValidator(|value| parse_server_address(value).map(|_| ())),
                         ))
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Join Game"),
-                                button_text_font.clone(),
-                                TextColor(TEXT_COLOR),
-                            ));
-                        });
+                        .entry::<Node>()
+                        .and_modify(compact_text_input);
 
                     parent
                         .spawn((
@@ -336,53 +740,177 @@ pub fn online_game_menu_setup(mut commands: Commands) {
         });
 }
 
+type MenuButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        &'static MenuButtonAction,
+        Option<&'static ButtonDisabled>,
+    ),
+    (Changed<Interaction>, With<Button>),
+>;
+
+fn pressed_actions<'a>(
+    interaction_query: &'a MenuButtonQuery,
+) -> impl Iterator<Item = &'a MenuButtonAction> {
+    interaction_query
+        .iter()
+        .filter(|(interaction, _, disabled)| {
+            **interaction == Interaction::Pressed && disabled.is_none()
+        })
+        .map(|(_, menu_button_action, _)| menu_button_action)
+}
+
 pub fn menu_action(
-    interaction_query: Query<
-        (&Interaction, &MenuButtonAction, Option<&ButtonDisabled>),
-        (Changed<Interaction>, With<Button>),
-    >,
+    interaction_query: MenuButtonQuery,
     mut app_exit_events: EventWriter<AppExit>,
     mut add_player_events: EventWriter<AddPlayer>,
     mut remove_player_events: EventWriter<RemovePlayer>,
     mut menu_state: ResMut<NextState<MenuState>>,
+    mut game_settings: ResMut<GameSettings>,
+    mut preferences: ResMut<Preferences>,
+) {
+    for menu_button_action in pressed_actions(&interaction_query) {
+        match menu_button_action {
+            MenuButtonAction::Quit => {
+                app_exit_events.send(AppExit::Success);
+            }
+            MenuButtonAction::PlayLocalGame => menu_state.set(MenuState::LocalGame),
+            MenuButtonAction::PlayOnlineGame => menu_state.set(MenuState::OnlineGame),
+            // Starting a game reads the whole form, so each has a system of its own
+            MenuButtonAction::ConfirmLocalGame | MenuButtonAction::ConfirmOnlineGame => (),
+            MenuButtonAction::Settings => menu_state.set(MenuState::Settings),
+            MenuButtonAction::CycleSetting(option) => {
+                cycle_setting_option(*option, &mut preferences);
+            }
+            MenuButtonAction::BackToMainMenu => {
+                menu_state.set(MenuState::Main);
+            }
+            MenuButtonAction::AddLocalPlayer => {
+                add_player_events.send(AddPlayer);
+            }
+            MenuButtonAction::RemoveLocalPlayer => {
+                remove_player_events.send(RemovePlayer);
+            }
+            MenuButtonAction::CycleSeatType(player_id) => {
+                let seat_type = next_seat_type(game_settings.seat_type(*player_id));
+                game_settings.set_seat_type(*player_id, seat_type);
+            }
+            MenuButtonAction::ToggleHandoff => {
+                game_settings.hot_seat_handoff = !game_settings.hot_seat_handoff;
+            }
+            MenuButtonAction::CycleRule(option) => {
+                cycle_rule_option(*option, &mut game_settings);
+            }
+        }
+    }
+}
+
+// The inputs of the local setup screen that are only read when the game starts
+#[derive(SystemParam)]
+pub struct LocalSetupForm<'w, 's> {
+    player_count_query: Query<'w, 's, &'static NumberOfLocalPLayers>,
+    seat_name_query: Query<'w, 's, (&'static TextInput, &'static SeatNameInput)>,
+    seed_input_query: Query<'w, 's, &'static TextInput, With<SeedInput>>,
+    error_text_query: Query<'w, 's, &'static mut Text, With<SetupErrorText>>,
+}
+
+pub fn confirm_local_game(
+    interaction_query: MenuButtonQuery,
+    mut menu_state: ResMut<NextState<MenuState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut game_settings: ResMut<GameSettings>,
+    mut setup_form: LocalSetupForm,
+) {
+    for menu_button_action in pressed_actions(&interaction_query) {
+        if !matches!(menu_button_action, MenuButtonAction::ConfirmLocalGame) {
+            continue;
+        }
+
+        // Nothing is kept unless the whole setup is valid
+        let mut settings = game_settings.clone();
+        settings.player_count = setup_form.player_count_query.single().0;
+
+        let mut player_names = vec![String::new(); MAX_PLAYERS];
+        for (input, seat) in setup_form.seat_name_query.iter() {
+            player_names[seat.0] = input.value.clone();
+        }
+        settings.player_names = player_names;
+
+        let seed = setup_form
+            .seed_input_query
+            .get_single()
+            .map_or("", |input| &input.value);
+        let result = parse_seed(seed).and_then(|seed| {
+            settings.seed = seed;
+            settings
+                .logic_settings()
+                .validate()
+                .map_err(|err| err.to_string())
+        });
+
+        if let Err(err) = result {
+            warn!("Invalid game settings: {}", err);
+            for mut text in setup_form.error_text_query.iter_mut() {
+                text.0 = err.clone();
+            }
+            continue;
+        }
+
+        *game_settings = settings;
+        menu_state.set(MenuState::Disabled);
+        game_state.set(GameState::LocalGameInit);
+    }
+}
+
+pub fn confirm_online_game(
+    interaction_query: MenuButtonQuery,
+    mut menu_state: ResMut<NextState<MenuState>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut game_settings: ResMut<GameSettings>,
-    player_count_query: Query<&NumberOfLocalPLayers>,
     room_input_query: Query<&TextInput, (With<RoomNameInput>, Without<PlayerNameInput>)>,
     player_input_query: Query<&TextInput, With<PlayerNameInput>>,
 ) {
-    for (interaction, menu_button_action, disabled) in &interaction_query {
-        if *interaction == Interaction::Pressed && disabled.is_none() {
-            match menu_button_action {
-                MenuButtonAction::Quit => {
-                    app_exit_events.send(AppExit::Success);
-                }
-                MenuButtonAction::PlayLocalGame => menu_state.set(MenuState::LocalGame),
-                MenuButtonAction::PlayOnlineGame => menu_state.set(MenuState::OnlineGame),
-                MenuButtonAction::ConfirmLocalGame => {
-                    game_settings.player_count = player_count_query.single().0;
-                    menu_state.set(MenuState::Disabled);
-                    game_state.set(GameState::LocalGameInit);
-                }
-                MenuButtonAction::ConfirmOnlineGame => {
-                    if let (Ok(room_input), Ok(player_input)) = (
-                        room_input_query.get_single(),
-                        player_input_query.get_single(),
-                    ) {
-                        game_settings.online_room_name = room_input.value.clone();
-                        game_settings.online_player_name = player_input.value.clone();
-                        menu_state.set(MenuState::Disabled);
-                        game_state.set(GameState::OnlineGameInit);
-                    }
-                }
-                MenuButtonAction::BackToMainMenu => {
-                    menu_state.set(MenuState::Main);
-                }
-                MenuButtonAction::AddLocalPlayer => {
-                    add_player_events.send(AddPlayer);
-                }
-                MenuButtonAction::RemoveLocalPlayer => {
-                    remove_player_events.send(RemovePlayer);
+    for menu_button_action in pressed_actions(&interaction_query) {
+        if !matches!(menu_button_action, MenuButtonAction::ConfirmOnlineGame) {
+            continue;
+        }
+
+        if let (Ok(room_input), Ok(player_input)) = (
+            room_input_query.get_single(),
+            player_input_query.get_single(),
+        ) {
+            game_settings.online_room_name = room_input.value.clone();
+            game_settings.online_player_name = player_input.value.clone();
+            // Seats in online rooms are not named on this device
+            game_settings.player_names.clear();
+            menu_state.set(MenuState::Disabled);
+            game_state.set(GameState::OnlineGameInit);
+        }
+    }
+}
+
+// Enter in the room name moves on to the player name, Enter there joins the game
+pub fn submit_online_form(
+    mut commands: Commands,
+    mut submitted_events: EventReader<TextInputSubmitted>,
+    room_input_query: Query<Entity, With<RoomNameInput>>,
+    player_input_query: Query<Entity, With<PlayerNameInput>>,
+    mut join_button_query: Query<(Entity, &MenuButtonAction, &mut Interaction)>,
+    mut button_focus: ResMut<ButtonFocus>,
+) {
+    for event in submitted_events.read() {
+        if room_input_query.contains(event.entity) {
+            for entity in player_input_query.iter() {
+                commands.entity(entity).insert(TextInputActive::default());
+            }
+        } else if player_input_query.contains(event.entity) {
+            for (entity, action, mut interaction) in join_button_query.iter_mut() {
+                if matches!(action, MenuButtonAction::ConfirmOnlineGame) {
+                    // Pressed like a button reached with the keys, so it is let go the same way
+                    *interaction = Interaction::Pressed;
+                    button_focus.pressed = Some(entity);
                 }
             }
         }
@@ -424,6 +952,368 @@ pub fn update_player_count_text(
     }
 }
 
+pub fn update_seat_type_buttons(
+    game_settings: Res<GameSettings>,
+    player_count_query: Query<Ref<NumberOfLocalPLayers>>,
+    mut seat_row_query: Query<(&SeatRow, &mut Node)>,
+    seat_button_query: Query<(&SeatTypeButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok(player_count) = player_count_query.get_single() else {
+        return;
+    };
+
+    if !game_settings.is_changed() && !player_count.is_changed() {
+        return;
+    }
+
+    for (seat_row, mut node) in &mut seat_row_query {
+        node.display = if seat_row.0 < player_count.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for (seat_button, children) in &seat_button_query {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = seat_type_label(game_settings.seat_type(seat_button.0));
+            }
+        }
+    }
+}
+
+pub fn update_rule_option_texts(
+    game_settings: Res<GameSettings>,
+    mut text_query: Query<(&mut Text, &RuleOptionText)>,
+) {
+    if !game_settings.is_changed() {
+        return;
+    }
+
+    for (mut text, rule_option) in text_query.iter_mut() {
+        text.0 = rule_option_label(rule_option.0, &game_settings);
+    }
+}
+
+// Typing a valid rank order switches the game to that deck, weakest rank first
+pub fn apply_custom_deck_input(
+    mut game_settings: ResMut<GameSettings>,
+    input_query: Query<&TextInput, (With<CustomDeckInput>, Changed<TextInput>)>,
+) {
+    for input in input_query.iter() {
+        if let Ok(deck) = DeckSpec::from_rank_list(&input.value)
+            && deck != game_settings.rules.deck
+        {
+            game_settings.rules.deck = deck;
+        }
+    }
+}
+
+// Once the input is left it shows the deck in play again, e.g. after cycling the presets
+pub fn update_custom_deck_input(
+    game_settings: Res<GameSettings>,
+    mut input_query: Query<&mut TextInput, (With<CustomDeckInput>, Without<TextInputActive>)>,
+) {
+    for mut input in input_query.iter_mut() {
+        if DeckSpec::from_rank_list(&input.value).ok().as_ref() != Some(&game_settings.rules.deck) {
+            input.value = game_settings.rules.deck.rank_list();
+            input.cursor = input.char_count();
+            input.selection_anchor = None;
+        }
+    }
+}
+
+pub fn update_setting_option_texts(
+    preferences: Res<Preferences>,
+    mut text_query: Query<(&mut Text, &SettingOptionText)>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    for (mut text, setting_option) in text_query.iter_mut() {
+        text.0 = setting_option_label(setting_option.0, &preferences);
+    }
+}
+
+// Typed settings are taken when their input is left, as long as they are valid
+pub fn apply_settings_inputs(
+    mut preferences: ResMut<Preferences>,
+    name_query: Query<&TextInput, (With<DefaultNameInput>, Without<TextInputActive>)>,
+    address_query: Query<&TextInput, (With<ServerAddressInput>, Without<TextInputActive>)>,
+) {
+    take_settings_inputs(&mut preferences, name_query.iter(), address_query.iter());
+}
+
+// Leaving the screen also takes the input that was still being typed in
+pub fn apply_settings_inputs_on_exit(
+    mut preferences: ResMut<Preferences>,
+    name_query: Query<&TextInput, With<DefaultNameInput>>,
+    address_query: Query<&TextInput, With<ServerAddressInput>>,
+) {
+    take_settings_inputs(&mut preferences, name_query.iter(), address_query.iter());
+}
+
+// Only real changes are written, since every change to the preferences is saved
+fn take_settings_inputs<'a>(
+    preferences: &mut ResMut<Preferences>,
+    name_inputs: impl Iterator<Item = &'a TextInput>,
+    address_inputs: impl Iterator<Item = &'a TextInput>,
+) {
+    for input in name_inputs {
+        let name = input.value.trim();
+        if name != preferences.player_name {
+            preferences.player_name = name.to_string();
+        }
+    }
+
+    for input in address_inputs {
+        if let Ok(server_address) = parse_server_address(&input.value)
+            && server_address != preferences.server_address
+        {
+            preferences.server_address = server_address;
+        }
+    }
+}
+
+pub fn update_handoff_toggle_text(
+    game_settings: Res<GameSettings>,
+    mut text_query: Query<&mut Text, With<HandoffToggleText>>,
+) {
+    if !game_settings.is_changed() {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.0 = handoff_label(game_settings.hot_seat_handoff);
+    }
+}
+
+fn handoff_label(hot_seat_handoff: bool) -> String {
+    if hot_seat_handoff {
+        "Hide hands between players: On".to_string()
+    } else {
+        "Hide hands between players: Off".to_string()
+    }
+}
+
+fn setting_option_label(option: SettingOption, preferences: &Preferences) -> String {
+    let on_off = |on: bool| if on { "On" } else { "Off" };
+
+    match option {
+        SettingOption::WindowSize => format!(
+            "Window size: {}x{}",
+            preferences.window_width, preferences.window_height
+        ),
+        SettingOption::Fullscreen => format!("Fullscreen: {}", on_off(preferences.fullscreen)),
+        SettingOption::VSync => format!("VSync: {}", on_off(preferences.vsync)),
+        SettingOption::AnimationSpeed => animation_speed_text(preferences.animation_speed),
+        SettingOption::BotSpeed => bot_speed_text(preferences.bot_think_delay),
+    }
+}
+
+fn cycle_setting_option(option: SettingOption, preferences: &mut Preferences) {
+    match option {
+        SettingOption::WindowSize => {
+            let size = (preferences.window_width, preferences.window_height);
+            (preferences.window_width, preferences.window_height) = next_in(&WINDOW_SIZES, size);
+        }
+        SettingOption::Fullscreen => preferences.fullscreen = !preferences.fullscreen,
+        SettingOption::VSync => preferences.vsync = !preferences.vsync,
+        SettingOption::AnimationSpeed => {
+            let speeds = ANIMATION_SPEEDS.map(|(_, speed)| speed);
+            preferences.animation_speed = next_in(&speeds, preferences.animation_speed);
+        }
+        SettingOption::BotSpeed => {
+            let delays = BOT_SPEEDS.map(|(_, delay)| delay);
+            preferences.bot_think_delay = next_in(&delays, preferences.bot_think_delay);
+        }
+    }
+}
+
+fn seat_type_label(seat_type: SeatType) -> String {
+    match seat_type {
+        SeatType::Human => "Human".to_string(),
+        SeatType::Bot(difficulty) => format!("Bot ({:?})", difficulty),
+    }
+}
+
+fn rule_option_label(option: RuleOption, game_settings: &GameSettings) -> String {
+    let rules = &game_settings.rules;
+
+    match option {
+        RuleOption::CardCount => format!("Cards at start: {}", game_settings.inital_card_count),
+        RuleOption::StartingLives => match rules.starting_lives {
+            Some(lives) => format!("Starting lives: {}", lives),
+            None => "Starting lives: Same as cards".to_string(),
+        },
+        RuleOption::Trump => match rules.trump {
+            TrumpRule::None => "Trump: None".to_string(),
+            TrumpRule::Vira => "Trump: Vira".to_string(),
+        },
+        RuleOption::Tie => match rules.tie {
+            TieRule::SuitBreaks => "Ties: Broken by suit".to_string(),
+            TieRule::Cancel(CancelledTrick::NoWinner) => "Ties: Cancel, no winner".to_string(),
+            TieRule::Cancel(CancelledTrick::CarryOver) => "Ties: Cancel, carry over".to_string(),
+        },
+        RuleOption::BlindRound => {
+            if rules.blind_one_card_round {
+                "Blind one-card round: On".to_string()
+            } else {
+                "Blind one-card round: Off".to_string()
+            }
+        }
+        RuleOption::Schedule => match rules.schedule {
+            RoundSchedule::ShrinkingHands => "Rounds: Hands shrink with lives".to_string(),
+            RoundSchedule::AscendingDescending { max_hand_size } => {
+                format!("Rounds: Up to {} cards and back", max_hand_size)
+            }
+        },
+        RuleOption::LifeLoss => match rules.life_loss {
+            LifeLoss::OnePerMiss => "Missed guess: Lose one life".to_string(),
+            LifeLoss::Difference => "Missed guess: Lose the difference".to_string(),
+        },
+        RuleOption::Deck => {
+            let deck = if rules.deck == DeckSpec::classic() {
+                "Classic"
+            } else if rules.deck == DeckSpec::poker() {
+                "Poker"
+            } else if rules.deck == DeckSpec::truco() {
+                "Truco"
+            } else {
+                "Custom"
+            };
+            format!("Deck: {}", deck)
+        }
+        RuleOption::DeckCount => format!("Decks: {}", rules.deck_count),
+        RuleOption::Undo => match rules.undo {
+            UndoRule::Disabled => "Undo: Off".to_string(),
+            UndoRule::CurrentTrick => "Undo: Current trick".to_string(),
+            UndoRule::CurrentMatch => "Undo: Current match".to_string(),
+        },
+    }
+}
+
+fn cycle_rule_option(option: RuleOption, game_settings: &mut GameSettings) {
+    let card_count = game_settings.inital_card_count;
+    let rules = &mut game_settings.rules;
+
+    match option {
+        RuleOption::CardCount => {
+            let card_count = card_count % MAX_CARD_COUNT + 1;
+            game_settings.inital_card_count = card_count;
+
+            // The largest hand of the climbing schedule follows the card count
+            if let RoundSchedule::AscendingDescending { .. } = game_settings.rules.schedule {
+                game_settings.rules.schedule = RoundSchedule::AscendingDescending {
+                    max_hand_size: card_count,
+                };
+            }
+        }
+        RuleOption::StartingLives => {
+            rules.starting_lives = match rules.starting_lives {
+                None => Some(1),
+                Some(lives) if lives >= MAX_STARTING_LIVES => None,
+                Some(lives) => Some(lives + 1),
+            };
+        }
+        RuleOption::Trump => {
+            rules.trump = match rules.trump {
+                TrumpRule::None => TrumpRule::Vira,
+                TrumpRule::Vira => TrumpRule::None,
+            };
+        }
+        RuleOption::Tie => {
+            rules.tie = match rules.tie {
+                TieRule::SuitBreaks => TieRule::Cancel(CancelledTrick::NoWinner),
+                TieRule::Cancel(CancelledTrick::NoWinner) => {
+                    TieRule::Cancel(CancelledTrick::CarryOver)
+                }
+                TieRule::Cancel(CancelledTrick::CarryOver) => TieRule::SuitBreaks,
+            };
+        }
+        RuleOption::BlindRound => {
+            rules.blind_one_card_round = !rules.blind_one_card_round;
+        }
+        RuleOption::Schedule => {
+            rules.schedule = match rules.schedule {
+                RoundSchedule::ShrinkingHands => RoundSchedule::AscendingDescending {
+                    max_hand_size: card_count,
+                },
+                RoundSchedule::AscendingDescending { .. } => RoundSchedule::ShrinkingHands,
+            };
+        }
+        RuleOption::LifeLoss => {
+            rules.life_loss = match rules.life_loss {
+                LifeLoss::OnePerMiss => LifeLoss::Difference,
+                LifeLoss::Difference => LifeLoss::OnePerMiss,
+            };
+        }
+        RuleOption::Deck => {
+            rules.deck = if rules.deck == DeckSpec::classic() {
+                DeckSpec::poker()
+            } else if rules.deck == DeckSpec::poker() {
+                DeckSpec::truco()
+            } else {
+                DeckSpec::classic()
+            };
+        }
+        RuleOption::DeckCount => {
+            rules.deck_count = rules.deck_count % MAX_DECK_COUNT + 1;
+        }
+        RuleOption::Undo => {
+            rules.undo = match rules.undo {
+                UndoRule::Disabled => UndoRule::CurrentTrick,
+                UndoRule::CurrentTrick => UndoRule::CurrentMatch,
+                UndoRule::CurrentMatch => UndoRule::Disabled,
+            };
+        }
+    }
+}
+
+// An empty seed means every game is dealt differently
+fn parse_seed(value: &str) -> Result<Option<u64>, String> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    value
+        .parse::<u64>()
+        .map(Some)
+        .map_err(|_| format!("The seed must be a whole number, got \"{}\"", value))
+}
+
+fn validate_seed(value: &str) -> Result<(), String> {
+    parse_seed(value).map(|_| ())
+}
+
+fn is_rank_list_character(character: char) -> bool {
+    character.is_ascii_digit() || "AJQKajqk, ".contains(character)
+}
+
+fn validate_rank_list(value: &str) -> Result<(), String> {
+    DeckSpec::from_rank_list(value).map(|_| ())
+}
+
+// Text inputs are spaced for a single field per row, the setup screen packs them tighter
+fn compact_text_input(mut node: Mut<Node>) {
+    node.margin = UiRect::all(Val::Px(4.0));
+    node.padding = UiRect::horizontal(Val::Px(10.0));
+}
+
+fn next_seat_type(seat_type: SeatType) -> SeatType {
+    match seat_type {
+        SeatType::Human => SeatType::Bot(BotDifficulty::Easy),
+        SeatType::Bot(BotDifficulty::Easy) => SeatType::Bot(BotDifficulty::Medium),
+        SeatType::Bot(BotDifficulty::Medium) => SeatType::Bot(BotDifficulty::Hard),
+        SeatType::Bot(BotDifficulty::Hard) => SeatType::Human,
+    }
+}
+
 pub fn enable_disable_add_player_button(
     mut commands: Commands,
     player_count_query: Query<&NumberOfLocalPLayers, Changed<NumberOfLocalPLayers>>,
@@ -9,7 +9,8 @@ pub fn setup(mut commands: Commands) {
 // Generic system that takes a component as a parameter, and will despawn all entities with that component
 pub fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
     for entity in &to_despawn {
-        commands.entity(entity).despawn_recursive();
+        // The match teardown can remove the same entities when leaving a game
+        commands.entity(entity).try_despawn_recursive();
     }
 }
 
@@ -1,17 +1,20 @@
 use bevy::{
     app::{App, Plugin, Update},
-    ecs::system::EntityCommands,
+    ecs::{schedule::IntoSystemConfigs, system::EntityCommands},
     hierarchy::{BuildChildren, ChildBuild, ChildBuilder},
-    text::{TextColor, TextFont},
+    text::{TextColor, TextFont, TextSpan},
     ui::{AlignItems, BackgroundColor, JustifyContent, Node, UiRect, Val, widget::Text},
     utils::default,
 };
-use components::{TextInput, TextInputLabel, TextInputValue};
+use components::{TextInput, TextInputLabel, TextInputSpan, TextInputValue};
+use events::TextInputSubmitted;
+use resources::TextClipboard;
 
 use crate::card_game::game_ui::{NORMAL_BUTTON, TEXT_COLOR};
 
 pub mod components;
 pub mod events;
+pub mod resources;
 pub mod systems;
 
 pub trait TextInputSpawner {
@@ -54,11 +57,7 @@ impl TextInputSpawner for ChildBuilder<'_> {
         };
 
         let mut result = self.spawn((
-            TextInput {
-                label: label.to_string(),
-                value: text.to_string(),
-                max_length,
-            },
+            TextInput::new(label, text, max_length),
             text_input_node.clone(),
             BackgroundColor(NORMAL_BUTTON),
         ));
@@ -71,12 +70,28 @@ impl TextInputSpawner for ChildBuilder<'_> {
                 TextInputLabel,
             ));
 
-            parent.spawn((
-                Text::new(""),
-                text_font.clone(),
-                TextColor(TEXT_COLOR),
-                TextInputValue,
-            ));
+            parent
+                .spawn((
+                    Text::new(""),
+                    text_font.clone(),
+                    TextColor(TEXT_COLOR),
+                    TextInputValue,
+                ))
+                .with_children(|parent| {
+                    for span in [
+                        TextInputSpan::SelectedBeforeCaret,
+                        TextInputSpan::Caret,
+                        TextInputSpan::SelectedAfterCaret,
+                        TextInputSpan::Rest,
+                    ] {
+                        parent.spawn((
+                            TextSpan::new(""),
+                            text_font.clone(),
+                            TextColor(TEXT_COLOR),
+                            span,
+                        ));
+                    }
+                });
         });
 
         result
@@ -87,14 +102,22 @@ pub struct TextInputPlugin;
 
 impl Plugin for TextInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                systems::set_text_input_active,
-                systems::handle_text_key_input,
-                systems::update_text_input_value,
-                systems::update_text_input_label,
-            ),
-        );
+        app.init_resource::<TextClipboard>()
+            .add_event::<TextInputSubmitted>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        systems::set_text_input_active,
+                        systems::handle_text_key_input,
+                        systems::handle_ime_input,
+                        systems::update_text_input_value,
+                        systems::blink_caret,
+                    )
+                        .chain(),
+                    systems::update_text_input_label,
+                    systems::toggle_ime,
+                ),
+            );
     }
 }
@@ -1,5 +1,6 @@
 use bevy::{
     ecs::component::Component,
+    time::{Timer, TimerMode},
     ui::{FocusPolicy, Interaction, Node},
 };
 
@@ -8,14 +9,149 @@ use bevy::{
 pub struct TextInput {
     pub label: String,
     pub value: String,
+    // Counted in characters, not bytes
     pub max_length: usize,
+    // The caret sits before the character with this index
+    pub cursor: usize,
+    // Where a selection was started, the selected text runs from here to the caret
+    pub selection_anchor: Option<usize>,
 }
 
+impl TextInput {
+    pub fn new(label: &str, value: &str, max_length: usize) -> Self {
+        Self {
+            label: label.to_string(),
+            value: value.to_string(),
+            max_length,
+            cursor: value.chars().count(),
+            selection_anchor: None,
+        }
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    // Start and end of the selected characters, None when nothing is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.selection_anchor
+            .filter(|anchor| *anchor != self.cursor)
+            .map(|anchor| (anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection()
+            .map(|(start, end)| self.value.chars().skip(start).take(end - start).collect())
+    }
+
+    // The text between two character indices
+    pub fn slice(&self, start: usize, end: usize) -> &str {
+        &self.value[self.byte_index(start)..self.byte_index(end)]
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    pub fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.selection_anchor = None;
+            return false;
+        };
+
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.value.replace_range(range, "");
+        self.cursor = start;
+        self.selection_anchor = None;
+        true
+    }
+
+    // Replaces the selection, anything past the maximum length is dropped
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+
+        let room = self.max_length.saturating_sub(self.char_count());
+        let text: String = text.chars().take(room).collect();
+        let index = self.byte_index(self.cursor);
+        self.value.insert_str(index, &text);
+        self.cursor += text.chars().count();
+    }
+
+    pub fn delete_backward(&mut self) {
+        if !self.delete_selection() && self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+
+    pub fn delete_forward(&mut self) {
+        if !self.delete_selection() && self.cursor < self.char_count() {
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+
+    // Holding shift grows the selection instead of dropping it
+    pub fn move_cursor(&mut self, cursor: usize, select: bool) {
+        if select {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = cursor.min(self.char_count());
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection_anchor = Some(0);
+        self.cursor = self.char_count();
+    }
+}
+
+// Shown greyed out while the input is empty
+#[derive(Component)]
+pub struct TextInputPlaceholder(pub String);
+
+// Characters the filter refuses are never typed or pasted
+#[derive(Component)]
+pub struct TextInputFilter(pub fn(char) -> bool);
+
+// Checked on every change, the value is shown in red while it is refused
+#[derive(Component)]
+pub struct TextInputValidator(pub fn(&str) -> Result<(), String>);
+
 #[derive(Component)]
 pub struct TextInputLabel;
 
 #[derive(Component)]
 pub struct TextInputValue;
 
+// The pieces the value is drawn in, so the caret and the selection can stand out
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum TextInputSpan {
+    SelectedBeforeCaret,
+    Caret,
+    SelectedAfterCaret,
+    Rest,
+}
+
 #[derive(Component)]
-pub struct TextInputActive;
+pub struct TextInputActive {
+    pub caret_blink: Timer,
+    pub caret_visible: bool,
+    // Keys go to the input method while it is putting a character together
+    pub composing: bool,
+}
+
+impl Default for TextInputActive {
+    fn default() -> Self {
+        Self {
+            caret_blink: Timer::from_seconds(0.5, TimerMode::Repeating),
+            caret_visible: true,
+            composing: false,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::ecs::{entity::Entity, event::Event};
+
+// Sent when Enter is pressed in a text input, the value is read from the input itself
+#[derive(Event)]
+pub struct TextInputSubmitted {
+    pub entity: Entity,
+}
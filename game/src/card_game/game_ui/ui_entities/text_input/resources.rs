@@ -0,0 +1,47 @@
+use std::sync::Mutex;
+
+use bevy::{ecs::system::Resource, log::warn};
+
+// Copy and paste go through the system clipboard, so text can be shared with other applications.
+// When there is none, e.g. without a display server, they still work between the game's own inputs
+#[derive(Resource)]
+pub struct TextClipboard {
+    system: Option<Mutex<arboard::Clipboard>>,
+    fallback: String,
+}
+
+impl Default for TextClipboard {
+    fn default() -> Self {
+        let system = match arboard::Clipboard::new() {
+            Ok(clipboard) => Some(Mutex::new(clipboard)),
+            Err(err) => {
+                warn!("The system clipboard is not available: {}", err);
+                None
+            }
+        };
+
+        Self {
+            system,
+            fallback: String::new(),
+        }
+    }
+}
+
+impl TextClipboard {
+    pub fn get_text(&mut self) -> String {
+        self.system
+            .as_mut()
+            .and_then(|clipboard| clipboard.get_mut().ok()?.get_text().ok())
+            .unwrap_or_else(|| self.fallback.clone())
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        if let Some(Ok(clipboard)) = self.system.as_mut().map(Mutex::get_mut)
+            && let Err(err) = clipboard.set_text(text.clone())
+        {
+            warn!("Could not copy to the system clipboard: {}", err);
+        }
+
+        self.fallback = text;
+    }
+}
@@ -1,78 +1,317 @@
 use bevy::{
+    color::{
+        Color,
+        palettes::css::{TOMATO, YELLOW},
+    },
     ecs::{
         entity::Entity,
-        event::EventReader,
-        query::{Changed, With, Without},
-        system::{Commands, Query, Single},
+        event::{EventReader, EventWriter},
+        query::{Changed, Has, With, Without},
+        system::{Commands, Query, Res, ResMut},
     },
     hierarchy::Children,
-    input::keyboard::KeyboardInput,
+    input::{
+        ButtonInput,
+        keyboard::{Key, KeyCode, KeyboardInput},
+        mouse::MouseButton,
+    },
+    text::{TextColor, TextSpan},
+    time::Time,
     ui::{Interaction, widget::Text},
+    window::{Ime, PrimaryWindow, Window},
 };
 
-use super::components::{TextInput, TextInputActive, TextInputLabel, TextInputValue};
+use crate::card_game::game_ui::TEXT_COLOR;
+
+use super::{
+    components::{
+        TextInput, TextInputActive, TextInputFilter, TextInputLabel, TextInputPlaceholder,
+        TextInputSpan, TextInputValidator, TextInputValue,
+    },
+    events::TextInputSubmitted,
+    resources::TextClipboard,
+};
 
+const PLACEHOLDER_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+// Clicking an input starts typing in it, clicking anywhere else stops
 pub fn set_text_input_active(
     mut commands: Commands,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
     mut text_input_query: Query<
-        (Entity, &Interaction, &TextInput),
+        (Entity, &Interaction, &mut TextInput),
         (Changed<Interaction>, Without<TextInputActive>),
     >,
-    active_text_input: Option<Single<Entity, With<TextInputActive>>>,
+    active_text_input_query: Query<(Entity, &Interaction), With<TextInputActive>>,
 ) {
-    for (entity, interaction, _) in &mut text_input_query {
-        match *interaction {
-            Interaction::Pressed => {
-                if let Some(active_entity) = active_text_input.as_ref() {
-                    commands.entity(**active_entity).remove::<TextInputActive>();
-                }
-                commands.entity(entity).insert(TextInputActive);
+    for (entity, interaction, mut text_input) in &mut text_input_query {
+        if *interaction == Interaction::Pressed {
+            for (active_entity, _) in active_text_input_query.iter() {
+                commands.entity(active_entity).remove::<TextInputActive>();
+            }
+            commands.entity(entity).insert(TextInputActive::default());
+
+            let end = text_input.char_count();
+            text_input.move_cursor(end, false);
+            return;
+        }
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        for (active_entity, interaction) in active_text_input_query.iter() {
+            if *interaction != Interaction::Pressed {
+                commands.entity(active_entity).remove::<TextInputActive>();
             }
-            _ => (),
         }
     }
 }
 
 pub fn handle_text_key_input(
+    mut commands: Commands,
     mut key_pressed: EventReader<KeyboardInput>,
-    mut text_input_active_query: Query<(&mut TextInput, &TextInputActive)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut clipboard: ResMut<TextClipboard>,
+    mut submitted_events: EventWriter<TextInputSubmitted>,
+    mut text_input_active_query: Query<(
+        Entity,
+        &mut TextInput,
+        &mut TextInputActive,
+        Option<&TextInputFilter>,
+    )>,
 ) {
+    let Ok((entity, mut text_input, mut active, filter)) = text_input_active_query.get_single_mut()
+    else {
+        key_pressed.clear();
+        return;
+    };
+
+    // AltGr is reported as Ctrl+Alt on Windows, and the characters it types are not shortcuts
+    let shortcut = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]) && !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let select = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
     for event in key_pressed.read() {
-        if event.state.is_pressed() {
-            for (mut text_input, _) in &mut text_input_active_query {
-                match event.key_code {
-                    bevy::input::keyboard::KeyCode::Backspace => {
-                        text_input.value.pop();
+        if !event.state.is_pressed() || active.composing {
+            continue;
+        }
+
+        // The caret stays visible while typing
+        active.caret_blink.reset();
+        active.caret_visible = true;
+
+        match &event.logical_key {
+            Key::Character(character) if shortcut => match character.to_lowercase().as_str() {
+                "a" => text_input.select_all(),
+                "c" => {
+                    if let Some(selected) = text_input.selected_text() {
+                        clipboard.set_text(selected);
                     }
-                    _ => {
-                        if text_input.value.len() < text_input.max_length {
-                            match key_code_to_char(event.key_code) {
-                                Some(c) => {
-                                    text_input.value.push(c);
-                                }
-                                None => (),
-                            };
-                        }
+                }
+                "x" => {
+                    if let Some(selected) = text_input.selected_text() {
+                        clipboard.set_text(selected);
+                        text_input.delete_selection();
                     }
                 }
+                "v" => insert_filtered(&mut text_input, &clipboard.get_text(), filter),
+                _ => (),
+            },
+            Key::Character(character) => insert_filtered(&mut text_input, character, filter),
+            Key::Space => insert_filtered(&mut text_input, " ", filter),
+            Key::Backspace => text_input.delete_backward(),
+            Key::Delete => text_input.delete_forward(),
+            Key::ArrowLeft => {
+                // Without shift the caret jumps to the start of the selection
+                let cursor = match text_input.selection() {
+                    Some((start, _)) if !select => start,
+                    _ => text_input.cursor.saturating_sub(1),
+                };
+                text_input.move_cursor(cursor, select);
+            }
+            Key::ArrowRight => {
+                let cursor = match text_input.selection() {
+                    Some((_, end)) if !select => end,
+                    _ => text_input.cursor + 1,
+                };
+                text_input.move_cursor(cursor, select);
+            }
+            Key::Home => text_input.move_cursor(0, select),
+            Key::End => {
+                let end = text_input.char_count();
+                text_input.move_cursor(end, select);
+            }
+            Key::Enter => {
+                submitted_events.send(TextInputSubmitted { entity });
+                commands.entity(entity).remove::<TextInputActive>();
+                break;
+            }
+            Key::Escape => {
+                commands.entity(entity).remove::<TextInputActive>();
+                break;
+            }
+            _ => (),
+        }
+    }
+}
+
+// Characters put together by an input method, e.g. for accents or CJK text, arrive here
+pub fn handle_ime_input(
+    mut ime_events: EventReader<Ime>,
+    mut text_input_active_query: Query<(
+        &mut TextInput,
+        &mut TextInputActive,
+        Option<&TextInputFilter>,
+    )>,
+) {
+    for event in ime_events.read() {
+        for (mut text_input, mut active, filter) in text_input_active_query.iter_mut() {
+            match event {
+                Ime::Preedit { value, .. } => active.composing = !value.is_empty(),
+                Ime::Commit { value, .. } => {
+                    active.composing = false;
+                    insert_filtered(&mut text_input, value, filter);
+                }
+                _ => (),
             }
         }
     }
 }
 
+fn insert_filtered(text_input: &mut TextInput, text: &str, filter: Option<&TextInputFilter>) {
+    let text: String = text
+        .chars()
+        .filter(|character| !character.is_control())
+        .filter(|character| filter.is_none_or(|filter| (filter.0)(*character)))
+        .collect();
+
+    if !text.is_empty() {
+        text_input.insert(&text);
+    }
+}
+
+// The value is drawn as the text before the selection, the selection on both sides of the caret
+// and the rest, so the pieces can be coloured separately
 pub fn update_text_input_value(
-    text_input_query: Query<(&Children, &TextInput), Changed<TextInput>>,
-    mut text_query: Query<&mut Text, With<TextInputValue>>,
+    text_input_query: Query<(
+        &Children,
+        &TextInput,
+        Option<&TextInputPlaceholder>,
+        Option<&TextInputValidator>,
+        Has<TextInputActive>,
+    )>,
+    mut value_query: Query<(&Children, &mut Text, &mut TextColor), With<TextInputValue>>,
+    mut span_query: Query<(&TextInputSpan, &mut TextSpan, &mut TextColor), Without<TextInputValue>>,
 ) {
-    for (text_input_children, text_input) in &text_input_query {
+    for (text_input_children, text_input, placeholder, validator, active) in &text_input_query {
+        let cursor = text_input.cursor.min(text_input.char_count());
+        let (start, end) = text_input.selection().unwrap_or((cursor, cursor));
+        let valid = validator.is_none_or(|validator| (validator.0)(&text_input.value).is_ok());
+        let color = if valid {
+            TEXT_COLOR
+        } else {
+            Color::from(TOMATO)
+        };
+        let show_placeholder = text_input.value.is_empty() && !active;
+
         for child in text_input_children.iter() {
-            if let Ok(mut text) = text_query.get_mut(*child) {
-                text.0 = text_input.value.clone();
+            let Ok((value_children, mut text, mut text_color)) = value_query.get_mut(*child) else {
+                continue;
+            };
+
+            set_if_different(&mut text.0, text_input.slice(0, start));
+            if text_color.0 != color {
+                text_color.0 = color;
+            }
+
+            for span_entity in value_children.iter() {
+                let Ok((span, mut span_text, mut span_color)) = span_query.get_mut(*span_entity)
+                else {
+                    continue;
+                };
+
+                let (value, span_color_value) = match span {
+                    TextInputSpan::SelectedBeforeCaret => {
+                        (text_input.slice(start, cursor), Color::from(YELLOW))
+                    }
+                    // Its colour is left to the blinking
+                    TextInputSpan::Caret => {
+                        set_if_different(&mut span_text.0, if active { "|" } else { "" });
+                        continue;
+                    }
+                    TextInputSpan::SelectedAfterCaret => {
+                        (text_input.slice(cursor, end), Color::from(YELLOW))
+                    }
+                    TextInputSpan::Rest => match placeholder {
+                        Some(placeholder) if show_placeholder => {
+                            (placeholder.0.as_str(), PLACEHOLDER_COLOR)
+                        }
+                        _ => (text_input.slice(end, text_input.char_count()), color),
+                    },
+                };
+
+                set_if_different(&mut span_text.0, value);
+                if span_color.0 != span_color_value {
+                    span_color.0 = span_color_value;
+                }
+            }
+        }
+    }
+}
+
+// Only writing real changes keeps the text from being laid out again every frame
+fn set_if_different(text: &mut String, value: &str) {
+    if text != value {
+        *text = value.to_string();
+    }
+}
+
+pub fn blink_caret(
+    time: Res<Time>,
+    mut text_input_active_query: Query<(&Children, &mut TextInputActive)>,
+    value_query: Query<&Children, With<TextInputValue>>,
+    mut span_query: Query<(&TextInputSpan, &mut TextColor)>,
+) {
+    for (text_input_children, mut active) in text_input_active_query.iter_mut() {
+        if active.caret_blink.tick(time.delta()).just_finished() {
+            active.caret_visible = !active.caret_visible;
+        }
+
+        let caret_color = if active.caret_visible {
+            TEXT_COLOR
+        } else {
+            Color::NONE
+        };
+
+        for value_children in value_query.iter_many(text_input_children) {
+            let mut spans = span_query.iter_many_mut(value_children);
+            while let Some((span, mut span_color)) = spans.fetch_next() {
+                if *span == TextInputSpan::Caret && span_color.0 != caret_color {
+                    span_color.0 = caret_color;
+                }
             }
         }
     }
 }
 
+// The input method is only asked for while someone is typing
+pub fn toggle_ime(
+    text_input_active_query: Query<(), With<TextInputActive>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    let enabled = !text_input_active_query.is_empty();
+    if window.ime_enabled != enabled {
+        window.ime_enabled = enabled;
+    }
+}
+
 pub fn update_text_input_label(
     text_input_query: Query<(&Children, &TextInput), Changed<TextInput>>,
     mut text_query: Query<&mut Text, With<TextInputLabel>>,
@@ -85,36 +324,3 @@ pub fn update_text_input_label(
         }
     }
 }
-
-fn key_code_to_char(keycode: bevy::input::keyboard::KeyCode) -> Option<char> {
-    match keycode {
-        bevy::input::keyboard::KeyCode::KeyA => Some('a'),
-        bevy::input::keyboard::KeyCode::KeyB => Some('b'),
-        bevy::input::keyboard::KeyCode::KeyC => Some('c'),
-        bevy::input::keyboard::KeyCode::KeyD => Some('d'),
-        bevy::input::keyboard::KeyCode::KeyE => Some('e'),
-        bevy::input::keyboard::KeyCode::KeyF => Some('f'),
-        bevy::input::keyboard::KeyCode::KeyG => Some('g'),
-        bevy::input::keyboard::KeyCode::KeyH => Some('h'),
-        bevy::input::keyboard::KeyCode::KeyI => Some('i'),
-        bevy::input::keyboard::KeyCode::KeyJ => Some('j'),
-        bevy::input::keyboard::KeyCode::KeyK => Some('k'),
-        bevy::input::keyboard::KeyCode::KeyL => Some('l'),
-        bevy::input::keyboard::KeyCode::KeyM => Some('m'),
-        bevy::input::keyboard::KeyCode::KeyN => Some('n'),
-        bevy::input::keyboard::KeyCode::KeyO => Some('o'),
-        bevy::input::keyboard::KeyCode::KeyP => Some('p'),
-        bevy::input::keyboard::KeyCode::KeyQ => Some('q'),
-        bevy::input::keyboard::KeyCode::KeyR => Some('r'),
-        bevy::input::keyboard::KeyCode::KeyS => Some('s'),
-        bevy::input::keyboard::KeyCode::KeyT => Some('t'),
-        bevy::input::keyboard::KeyCode::KeyU => Some('u'),
-        bevy::input::keyboard::KeyCode::KeyV => Some('v'),
-        bevy::input::keyboard::KeyCode::KeyW => Some('w'),
-        bevy::input::keyboard::KeyCode::KeyX => Some('x'),
-        bevy::input::keyboard::KeyCode::KeyY => Some('y'),
-        bevy::input::keyboard::KeyCode::KeyZ => Some('z'),
-        bevy::input::keyboard::KeyCode::Space => Some(' '),
-        _ => None,
-    }
-}
@@ -0,0 +1,231 @@
+use std::net::SocketAddr;
+
+use bevy::{
+    ecs::{
+        change_detection::DetectChanges,
+        query::With,
+        system::{Query, Res, ResMut, Resource},
+    },
+    log::warn,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, Window, WindowMode},
+};
+use card_game_logic::server_options;
+
+use super::{
+    GameSettings,
+    config::{parse_entries, read_config, write_config},
+};
+
+pub const PREFERENCES_FILE: &str = "settings.cfg";
+
+// Seconds bots wait before acting
+pub const BOT_SPEEDS: [(&str, f32); 3] = [("Fast", 0.4), ("Normal", 1.0), ("Slow", 2.0)];
+// Animation speeds as multipliers
+pub const ANIMATION_SPEEDS: [(&str, f32); 3] = [("Slow", 0.5), ("Normal", 1.0), ("Fast", 2.0)];
+pub const WINDOW_SIZES: [(f32, f32); 4] = [
+    (1280.0, 720.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (1024.0, 768.0),
+];
+
+// What the player chose in the settings menu, kept between runs
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Preferences {
+    pub window_width: f32,
+    pub window_height: f32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub animation_speed: f32,
+    pub bot_think_delay: f32,
+    // Given to the first local seat and used when joining online rooms
+    pub player_name: String,
+    pub server_address: SocketAddr,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            window_width: 1280.0,
+            window_height: 720.0,
+            fullscreen: false,
+            vsync: true,
+            animation_speed: 1.0,
+            bot_think_delay: 1.0,
+            player_name: String::new(),
+            server_address: server_options::get_server_addr(),
+        }
+    }
+}
+
+impl Preferences {
+    // Defaults are used until the settings are first changed
+    pub fn load() -> Result<Self, String> {
+        match read_config(PREFERENCES_FILE)? {
+            Some(text) => Self::from_config(&text),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        write_config(PREFERENCES_FILE, &self.to_config())
+    }
+
+    pub fn to_config(&self) -> String {
+        [
+            format!("window_width = {}", self.window_width),
+            format!("window_height = {}", self.window_height),
+            format!("fullscreen = {}", self.fullscreen),
+            format!("vsync = {}", self.vsync),
+            format!("animation_speed = {}", self.animation_speed),
+            format!("bot_think_delay = {}", self.bot_think_delay),
+            format!("player_name = {}", self.player_name),
+            format!("server_address = {}", self.server_address),
+        ]
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+    }
+
+    // Settings missing from the file keep their defaults, unknown ones are skipped so files from
+    // newer versions still load
+    pub fn from_config(text: &str) -> Result<Self, String> {
+        let mut preferences = Self::default();
+
+        for (key, value) in parse_entries(text)? {
+            let invalid = || format!("Invalid value for {}: {}", key, value);
+
+            match key.as_str() {
+                "window_width" => {
+                    preferences.window_width = value.parse().map_err(|_| invalid())?
+                }
+                "window_height" => {
+                    preferences.window_height = value.parse().map_err(|_| invalid())?
+                }
+                "fullscreen" => preferences.fullscreen = value.parse().map_err(|_| invalid())?,
+                "vsync" => preferences.vsync = value.parse().map_err(|_| invalid())?,
+                "animation_speed" => {
+                    preferences.animation_speed = value.parse().map_err(|_| invalid())?
+                }
+                "bot_think_delay" => {
+                    preferences.bot_think_delay = value.parse().map_err(|_| invalid())?
+                }
+                "player_name" => preferences.player_name = value,
+                "server_address" => preferences.server_address = parse_server_address(&value)?,
+                _ => warn!("Skipping unknown setting {}", key),
+            }
+        }
+
+        Ok(preferences)
+    }
+
+    pub fn window(&self) -> Window {
+        let mut window = Window {
+            title: "Guessing Card Game".to_string(),
+            ..Default::default()
+        };
+        self.apply_to_window(&mut window);
+        window
+    }
+
+    fn apply_to_window(&self, window: &mut Window) {
+        if window.resolution.width() != self.window_width
+            || window.resolution.height() != self.window_height
+        {
+            window.resolution.set(self.window_width, self.window_height);
+        }
+
+        let mode = if self.fullscreen {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        } else {
+            WindowMode::Windowed
+        };
+        if window.mode != mode {
+            window.mode = mode;
+        }
+
+        let present_mode = if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+}
+
+pub fn parse_server_address(value: &str) -> Result<SocketAddr, String> {
+    match value.trim().parse::<SocketAddr>() {
+        Ok(SocketAddr::V4(addr)) => Ok(SocketAddr::V4(addr)),
+        _ => Err(format!(
+            "The server address must look like 127.0.0.1:54123, got \"{}\"",
+            value.trim()
+        )),
+    }
+}
+
+pub fn bot_speed_text(bot_think_delay: f32) -> String {
+    let label = BOT_SPEEDS
+        .iter()
+        .find(|(_, delay)| *delay == bot_think_delay)
+        .map(|(label, _)| label.to_string())
+        .unwrap_or_else(|| format!("{:.1}s", bot_think_delay));
+
+    format!("Bot speed: {}", label)
+}
+
+pub fn animation_speed_text(animation_speed: f32) -> String {
+    let label = ANIMATION_SPEEDS
+        .iter()
+        .find(|(_, speed)| *speed == animation_speed)
+        .map(|(label, _)| label.to_string())
+        .unwrap_or_else(|| format!("{:.1}x", animation_speed));
+
+    format!("Animations: {}", label)
+}
+
+// Moves to the entry after the current value, or the first one when it is not in the list
+pub fn next_in<T: Copy + PartialEq>(values: &[T], current: T) -> T {
+    let index = values.iter().position(|value| *value == current);
+    values[index.map_or(0, |index| (index + 1) % values.len())]
+}
+
+// Every change is pushed to the resources it affects and written to the settings file
+pub fn apply_preferences(
+    preferences: Res<Preferences>,
+    mut game_settings: ResMut<GameSettings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+
+    game_settings.animation_speed = preferences.animation_speed;
+    game_settings.bot_think_delay = preferences.bot_think_delay;
+
+    let name = preferences.player_name.trim();
+    if !name.is_empty() {
+        game_settings.online_player_name = name.to_string();
+        match game_settings.player_names.first_mut() {
+            Some(first_name) if first_name.trim().is_empty() => *first_name = name.to_string(),
+            Some(_) => (),
+            None => game_settings.player_names.push(name.to_string()),
+        }
+    }
+
+    if let Ok(mut window) = window_query.get_single_mut() {
+        preferences.apply_to_window(&mut window);
+    }
+
+    if let Err(err) = server_options::set_server_addr(preferences.server_address) {
+        warn!("{}", err);
+    }
+
+    // Loading them is not a change worth writing back
+    if !preferences.is_added()
+        && let Err(err) = preferences.save()
+    {
+        warn!("Could not save the settings: {}", err);
+    }
+}
@@ -2,15 +2,27 @@ use bevy::prelude::*;
 
 mod card_game;
 
+use card_game::preferences::Preferences;
+
 fn main() {
+    // Read before the app starts, since the window is created with the saved size
+    let (preferences, load_error) = match Preferences::load() {
+        Ok(preferences) => (preferences, None),
+        Err(err) => (Preferences::default(), Some(err)),
+    };
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Guessing Card Game".to_string(),
-                ..default()
-            }),
+            primary_window: Some(preferences.window()),
             ..default()
         }))
+        .insert_resource(preferences)
+        // Nothing is logged before the plugins are built, so the error waits until startup
+        .add_systems(Startup, move || {
+            if let Some(err) = &load_error {
+                warn!("Could not load the settings, using the defaults: {}", err);
+            }
+        })
         .add_plugins(card_game::CardGamePlugin)
         .run();
 }
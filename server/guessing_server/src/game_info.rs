@@ -1,6 +1,6 @@
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
-use card_game_logic::game_logic::{local::LocalGameLogic, GameSettings};
+use card_game_logic::game_logic::{common::GameOutcome, local::LocalGameLogic, GameSettings};
 
 use futures::lock::Mutex;
 
@@ -14,6 +14,7 @@ pub struct GameInfo {
     pub game_settings: GameSettings,
     pub game_logic: LocalGameLogic,
     pub player_info_map: HashMap<usize, PlayerInfo>,
+    pub outcome: Option<GameOutcome>,
 }
 
 pub struct GameInfoLookup {
@@ -42,6 +43,7 @@ impl GameInfoLookup {
             game_settings: GameSettings::default(),
             game_logic: LocalGameLogic::default(),
             player_info_map: HashMap::new(),
+            outcome: None,
         };
 
         game_info.player_info_map.insert(0, PlayerInfo {
@@ -58,6 +60,10 @@ impl GameInfoLookup {
         Ok(game_info)
     }
 
+    pub fn add_player_socket_addr(&mut self, socket_addr: SocketAddr, game_info: Arc<Mutex<GameInfo>>) {
+        self.game_info_by_socket_addr.insert(socket_addr, game_info);
+    }
+
     pub fn get_game_info_by_name(&self, name: &str) -> Option<Arc<Mutex<GameInfo>>> {
         self.game_info_by_name.get(name).cloned()
     }
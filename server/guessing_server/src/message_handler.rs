@@ -6,6 +6,7 @@ use futures::lock::Mutex;
 use crate::game_info::GameInfoLookup;
 
 mod add_player_handler;
+mod match_handler;
 
 pub fn handle_message(
     socket: &UdpSocket,
@@ -27,13 +28,17 @@ pub fn handle_message(
                 },
             )
         }
+        MessageType::StartMatch => {
+            println!("Player {} started the match", message.player_id);
+            match_handler::start_match(socket, message, response_address, game_info_lookup)
+        }
         MessageType::Guess => {
             println!("Player {} guessed", message.player_id);
-            Ok(())
+            match_handler::guess(socket, message, response_address, game_info_lookup)
         }
         MessageType::PlayCard => {
             println!("Player {} played a card", message.player_id);
-            Ok(())
+            match_handler::play_card(socket, message, response_address, game_info_lookup)
         }
         MessageType::PlayerJoined | MessageType::UpdateState => {
             Err("Server should not be getting this message".to_string())
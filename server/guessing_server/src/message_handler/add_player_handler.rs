@@ -53,7 +53,11 @@ fn on_player_socket_addr_valid(
     game_info_lookup: &mut GameInfoLookup,
 ) -> Result<usize, String> {
     match game_info_lookup.get_game_info_by_name(&room_name) {
-        Some(game_info) => on_room_exists(player_name, player_address, &game_info),
+        Some(game_info) => {
+            let player_id = on_room_exists(player_name, player_address, &game_info)?;
+            game_info_lookup.add_player_socket_addr(*player_address, game_info);
+            Ok(player_id)
+        }
         None => game_info_lookup
             .create_game_info(room_name.clone(), player_name.clone(), *player_address)
             .map(|_| 0),
@@ -75,7 +79,8 @@ fn on_room_exists(
             return Err("Player already in room".to_string());
         }
         None => {
-            let player_id = game_info.player_info_map.len() + 1;
+            // Seats are numbered from zero without gaps, the host being seat 0
+            let player_id = game_info.player_info_map.len();
             game_info.player_info_map.insert(
                 player_id,
                 PlayerInfo {
@@ -0,0 +1,162 @@
+use std::{
+    net::{SocketAddr, UdpSocket},
+    ops::DerefMut,
+};
+
+use card_game_logic::{
+    game_logic::{GameLogic, common::Card, events::GameEvent},
+    game_message::{GameMessage, MessageType},
+};
+use futures::{executor::block_on, lock::Mutex};
+
+use crate::game_info::{GameInfo, GameInfoLookup};
+
+pub fn start_match(
+    socket: &UdpSocket,
+    message: GameMessage,
+    response_address: &SocketAddr,
+    game_info_lookup: &Mutex<GameInfoLookup>,
+) -> Result<(), String> {
+    let card_count = parse_param::<usize>(&message, "card_count")?;
+
+    with_game_info(socket, response_address, game_info_lookup, |game_info| {
+        if find_player_id(game_info, response_address) != Some(0) {
+            return Err("Only the host can start the match".to_string());
+        }
+
+        game_info.game_settings.player_count = game_info.player_info_map.len();
+        game_info.game_settings.initial_card_count = card_count;
+        game_info
+            .game_settings
+            .validate()
+            .map_err(|err| err.to_string())?;
+
+        let rules = game_info.game_settings.rules.clone();
+        game_info
+            .game_logic
+            .init(game_info.game_settings.player_count, rules);
+        game_info
+            .game_logic
+            .start_match(card_count)
+            .map_err(|err| err.to_string())?;
+        game_info.outcome = None;
+
+        Ok(())
+    })
+}
+
+pub fn guess(
+    socket: &UdpSocket,
+    message: GameMessage,
+    response_address: &SocketAddr,
+    game_info_lookup: &Mutex<GameInfoLookup>,
+) -> Result<(), String> {
+    let guess = parse_param::<usize>(&message, "guess")?;
+
+    with_game_info(socket, response_address, game_info_lookup, |game_info| {
+        check_match_in_progress(game_info)?;
+        let player_id = find_player_id(game_info, response_address)
+            .ok_or_else(|| "Player not in room".to_string())?;
+
+        game_info.game_logic.set_guess(player_id, guess)
+    })
+}
+
+pub fn play_card(
+    socket: &UdpSocket,
+    message: GameMessage,
+    response_address: &SocketAddr,
+    game_info_lookup: &Mutex<GameInfoLookup>,
+) -> Result<(), String> {
+    let card = Card(
+        parse_param(&message, "card_suit")?,
+        parse_param(&message, "card_rank")?,
+    );
+
+    with_game_info(socket, response_address, game_info_lookup, |game_info| {
+        check_match_in_progress(game_info)?;
+        let player_id = find_player_id(game_info, response_address)
+            .ok_or_else(|| "Player not in room".to_string())?;
+
+        game_info.game_logic.play_card(player_id, &card)?;
+
+        Ok(())
+    })
+}
+
+fn with_game_info(
+    socket: &UdpSocket,
+    response_address: &SocketAddr,
+    game_info_lookup: &Mutex<GameInfoLookup>,
+    action: impl FnOnce(&mut GameInfo) -> Result<(), String>,
+) -> Result<(), String> {
+    let game_info = block_on(game_info_lookup.lock())
+        .get_game_info_by_socket_addr(response_address)
+        .ok_or_else(|| "Player not in a game".to_string())?;
+
+    let mut game_info = block_on(game_info.lock());
+    action(game_info.deref_mut())?;
+
+    // These are the state changes that will be broadcast to the room
+    for event in game_info.game_logic.take_events() {
+        if let GameEvent::GameOver { outcome } = event {
+            game_info.outcome = Some(outcome);
+        }
+    }
+
+    broadcast_views(socket, &game_info);
+
+    Ok(())
+}
+
+// Every player only gets the part of the state their seat is allowed to see
+fn broadcast_views(socket: &UdpSocket, game_info: &GameInfo) {
+    for player_info in game_info.player_info_map.values() {
+        let message = GameMessage {
+            player_id: player_info.player_id,
+            message_type: MessageType::UpdateState,
+            message_params: game_info
+                .game_logic
+                .get_player_view(player_info.player_id)
+                .to_message_params(),
+        };
+
+        let _ = socket.send_to(message.to_string().as_bytes(), player_info.player_ip);
+    }
+}
+
+// Moves are only taken between the host starting the match and the game ending
+fn check_match_in_progress(game_info: &GameInfo) -> Result<(), String> {
+    if game_info.game_logic.get_player_count() == 0 {
+        return Err("Match has not started".to_string());
+    }
+
+    if game_info.outcome.is_some() {
+        return Err("Game is over".to_string());
+    }
+
+    Ok(())
+}
+
+fn find_player_id(game_info: &GameInfo, address: &SocketAddr) -> Option<usize> {
+    game_info
+        .player_info_map
+        .values()
+        .find(|player_info| player_info.player_ip == *address)
+        .map(|player_info| player_info.player_id)
+}
+
+fn find_param<'a>(message: &'a GameMessage, key: &str) -> Result<&'a str, String> {
+    message
+        .message_params
+        .iter()
+        .find(|param| param.key == key)
+        .map(|param| param.value.as_str())
+        .ok_or_else(|| format!("Missing parameter {}", key))
+}
+
+fn parse_param<T: std::str::FromStr>(message: &GameMessage, key: &str) -> Result<T, String> {
+    find_param(message, key)?
+        .parse::<T>()
+        .map_err(|_| format!("Invalid parameter {}", key))
+}
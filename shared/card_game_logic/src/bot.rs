@@ -0,0 +1,192 @@
+use std::cmp::Ordering;
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::game_logic::{
+    common::{Card, PlayedCard},
+    rules::{CancelledTrick, RuleSet, TieRule},
+    view::PlayerView,
+};
+
+pub mod heuristic;
+pub mod monte_carlo;
+pub mod random;
+
+// Bots only get to see what their seat is allowed to know
+pub trait Bot {
+    fn choose_guess(&mut self, view: &PlayerView) -> usize;
+    fn choose_card(&mut self, view: &PlayerView) -> Card;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BotDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeatType {
+    Human,
+    Bot(BotDifficulty),
+}
+
+pub fn create_bot(difficulty: BotDifficulty) -> Box<dyn Bot + Send + Sync> {
+    create_bot_with_rng(difficulty, StdRng::from_os_rng())
+}
+
+pub fn create_seeded_bot(difficulty: BotDifficulty, seed: u64) -> Box<dyn Bot + Send + Sync> {
+    create_bot_with_rng(difficulty, StdRng::seed_from_u64(seed))
+}
+
+fn create_bot_with_rng(difficulty: BotDifficulty, rng: StdRng) -> Box<dyn Bot + Send + Sync> {
+    match difficulty {
+        BotDifficulty::Easy => Box::new(random::RandomBot::new(rng)),
+        BotDifficulty::Medium => Box::new(heuristic::HeuristicBot),
+        BotDifficulty::Hard => Box::new(monte_carlo::MonteCarloBot::new(200, rng)),
+    }
+}
+
+pub(crate) fn allowed_guesses(view: &PlayerView) -> Vec<usize> {
+    (0..=view.own_seat().hand_size)
+        .filter(|guess| Some(*guess) != view.forbidden_guess)
+        .collect()
+}
+
+// Empty while the own hand is hidden in the blind round
+pub(crate) fn own_hand(view: &PlayerView) -> &[Card] {
+    view.own_hand().map(Vec::as_slice).unwrap_or(&[])
+}
+
+pub(crate) fn closest_allowed_guess(allowed_guesses: &[usize], target: usize) -> usize {
+    *allowed_guesses
+        .iter()
+        .min_by_key(|guess| guess.abs_diff(target))
+        .unwrap_or(&0)
+}
+
+// Card strength for the current match, which depends on the rule set and the vira
+#[derive(Clone)]
+pub(crate) struct CardOrder {
+    rules: RuleSet,
+    vira: Option<Card>,
+}
+
+impl CardOrder {
+    pub(crate) fn new(view: &PlayerView) -> Self {
+        Self {
+            rules: view.rules.clone(),
+            vira: view.vira,
+        }
+    }
+
+    pub(crate) fn cmp(&self, a: &Card, b: &Card) -> Ordering {
+        self.rules.compare_cards(a, b, self.vira)
+    }
+
+    pub(crate) fn beats(&self, a: &Card, b: &Card) -> bool {
+        self.cmp(a, b) == Ordering::Greater
+    }
+
+    pub(crate) fn insert(&self, played_cards: &mut Vec<PlayedCard>, card: PlayedCard) {
+        self.rules.insert_played_card(played_cards, card, self.vira);
+    }
+
+    pub(crate) fn trick_winner(&self, played_cards: &[PlayedCard]) -> Option<usize> {
+        self.rules.trick_winner(played_cards)
+    }
+
+    pub(crate) fn carries_over(&self) -> bool {
+        self.rules.tie == TieRule::Cancel(CancelledTrick::CarryOver)
+    }
+
+    // Whether playing the card now would leave the player winning the current trick
+    pub(crate) fn would_win(
+        &self,
+        played_cards: &[PlayedCard],
+        card: Card,
+        player_id: usize,
+    ) -> bool {
+        let mut played_cards = played_cards.to_vec();
+        self.insert(
+            &mut played_cards,
+            PlayedCard {
+                player_id,
+                card,
+                cancelled: false,
+            },
+        );
+
+        self.trick_winner(&played_cards) == Some(player_id)
+    }
+}
+
+// Cards the player cannot see: everything outside their own hand, the hands shown to them in
+// the blind round, the current trick and the vira
+pub(crate) fn unseen_cards(view: &PlayerView) -> Vec<Card> {
+    let mut deck = view.rules.build_deck();
+
+    remove_known_cards(&mut deck, own_hand(view).iter().copied());
+    for (other, seat) in view.seats.iter().enumerate() {
+        if let Some(hand) = seat.hand.as_ref().filter(|_| other != view.player_id) {
+            remove_known_cards(&mut deck, hand.iter().copied());
+        }
+    }
+    remove_known_cards(&mut deck, view.played_cards.iter().map(|p| p.card));
+    remove_known_cards(&mut deck, view.vira);
+
+    deck
+}
+
+// Only one copy is removed per known card, so duplicates from other decks stay unseen
+fn remove_known_cards(deck: &mut Vec<Card>, known_cards: impl IntoIterator<Item = Card>) {
+    for card in known_cards {
+        if let Some(index) = deck.iter().position(|c| *c == card) {
+            deck.swap_remove(index);
+        }
+    }
+}
+
+// In the blind round the own card is unknown, so the chance of winning is the share of the
+// unseen cards that would beat every visible card
+pub(crate) fn blind_win_chance(view: &PlayerView) -> f64 {
+    let card_order = CardOrder::new(view);
+
+    let mut visible_cards = view.played_cards.clone();
+    for (other, seat) in view.seats.iter().enumerate() {
+        let Some(hand) = seat.hand.as_ref().filter(|_| other != view.player_id) else {
+            continue;
+        };
+
+        for card in hand {
+            card_order.insert(
+                &mut visible_cards,
+                PlayedCard {
+                    player_id: other,
+                    card: *card,
+                    cancelled: false,
+                },
+            );
+        }
+    }
+
+    let mut candidates = view.rules.build_deck();
+    remove_known_cards(&mut candidates, visible_cards.iter().map(|p| p.card));
+    remove_known_cards(&mut candidates, view.vira);
+    let winning_candidates = candidates
+        .iter()
+        .filter(|card| card_order.would_win(&visible_cards, **card, view.player_id))
+        .count();
+
+    winning_candidates as f64 / candidates.len().max(1) as f64
+}
+
+pub(crate) fn blind_guess(view: &PlayerView) -> usize {
+    let estimate = blind_win_chance(view).round() as usize;
+
+    closest_allowed_guess(&allowed_guesses(view), estimate)
+}
+
+pub(crate) fn has_played(played_cards: &[PlayedCard], player_id: usize) -> bool {
+    played_cards.iter().any(|p| p.player_id == player_id)
+}
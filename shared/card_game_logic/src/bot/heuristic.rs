@@ -0,0 +1,104 @@
+use crate::game_logic::{
+    common::{Card, PlayedCard},
+    view::PlayerView,
+};
+
+use super::{
+    Bot, CardOrder, allowed_guesses, blind_guess, closest_allowed_guess, own_hand, unseen_cards,
+};
+
+pub struct HeuristicBot;
+
+impl Bot for HeuristicBot {
+    fn choose_guess(&mut self, view: &PlayerView) -> usize {
+        if view.own_hand().is_none() {
+            return blind_guess(view);
+        }
+
+        let opponents = view
+            .seats
+            .iter()
+            .enumerate()
+            .filter(|(p, seat)| *p != view.player_id && seat.dealt_count > 0)
+            .count();
+
+        let estimate = estimate_tricks(
+            &CardOrder::new(view),
+            own_hand(view),
+            &unseen_cards(view),
+            opponents,
+        );
+
+        closest_allowed_guess(&allowed_guesses(view), estimate)
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        pick_card(
+            &CardOrder::new(view),
+            own_hand(view),
+            &view.played_cards,
+            view.player_id,
+            view.own_seat().guess,
+            view.own_seat().wins,
+        )
+    }
+}
+
+// Each card counts as the chance of it beating one random unseen card per opponent
+pub(crate) fn estimate_tricks(
+    card_order: &CardOrder,
+    hand: &[Card],
+    unseen_cards: &[Card],
+    opponents: usize,
+) -> usize {
+    let expected_tricks: f64 = hand
+        .iter()
+        .map(|card| {
+            let weaker_cards = unseen_cards
+                .iter()
+                .filter(|other| card_order.beats(card, other))
+                .count();
+            let strength = weaker_cards as f64 / unseen_cards.len().max(1) as f64;
+
+            strength.powi(opponents as i32)
+        })
+        .sum();
+
+    expected_tricks.round() as usize
+}
+
+// Plays to win while the guess is not reached yet and to lose afterwards
+pub(crate) fn pick_card(
+    card_order: &CardOrder,
+    hand: &[Card],
+    played_cards: &[PlayedCard],
+    player_id: usize,
+    guess: usize,
+    wins: usize,
+) -> Card {
+    let mut sorted_hand = hand.to_vec();
+    sorted_hand.sort_by(|a, b| card_order.cmp(a, b));
+
+    let lowest = *sorted_hand.first().expect("Bot has no cards to play");
+    let highest = *sorted_hand.last().unwrap();
+    let wants_to_win = wins < guess;
+
+    if played_cards.is_empty() {
+        return if wants_to_win { highest } else { lowest };
+    }
+
+    if wants_to_win {
+        sorted_hand
+            .iter()
+            .find(|card| card_order.would_win(played_cards, **card, player_id))
+            .copied()
+            .unwrap_or(lowest)
+    } else {
+        sorted_hand
+            .iter()
+            .rev()
+            .find(|card| !card_order.would_win(played_cards, **card, player_id))
+            .copied()
+            .unwrap_or(lowest)
+    }
+}
@@ -0,0 +1,235 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::game_logic::{
+    common::{Card, PlayedCard},
+    view::PlayerView,
+};
+
+use super::{
+    Bot, CardOrder, allowed_guesses, blind_guess, closest_allowed_guess, has_played,
+    heuristic::{estimate_tricks, pick_card},
+    own_hand, unseen_cards,
+};
+
+// Samples the hidden hands many times and plays each sample out with heuristic players
+pub struct MonteCarloBot {
+    samples: usize,
+    rng: StdRng,
+}
+
+struct Playout {
+    card_order: CardOrder,
+    hands: Vec<Vec<Card>>,
+    played_cards: Vec<PlayedCard>,
+    player_turn: usize,
+    guesses: Vec<usize>,
+    wins: Vec<usize>,
+    carried_tricks: usize,
+}
+
+impl MonteCarloBot {
+    pub fn new(samples: usize, rng: StdRng) -> Self {
+        Self { samples, rng }
+    }
+
+    fn determinize(&mut self, view: &PlayerView) -> Playout {
+        let mut unseen_cards = unseen_cards(view);
+        unseen_cards.shuffle(&mut self.rng);
+
+        let hands = view
+            .seats
+            .iter()
+            .enumerate()
+            .map(|(p, seat)| match &seat.hand {
+                _ if p == view.player_id => own_hand(view).to_vec(),
+                // Hands shown face up in the blind round are known, not sampled
+                Some(hand) => hand.clone(),
+                None => {
+                    let count = seat.hand_size.min(unseen_cards.len());
+                    unseen_cards.split_off(unseen_cards.len() - count)
+                }
+            })
+            .collect();
+
+        Playout {
+            card_order: CardOrder::new(view),
+            hands,
+            played_cards: view.played_cards.clone(),
+            player_turn: view.player_turn,
+            guesses: view.seats.iter().map(|seat| seat.guess).collect(),
+            wins: view.seats.iter().map(|seat| seat.wins).collect(),
+            carried_tricks: 0,
+        }
+    }
+}
+
+impl Bot for MonteCarloBot {
+    fn choose_guess(&mut self, view: &PlayerView) -> usize {
+        // With a single hidden card there is nothing left to sample
+        if view.own_hand().is_none() {
+            return blind_guess(view);
+        }
+
+        let player_id = view.player_id;
+        let hand_size = own_hand(view).len();
+        let mut outcomes = vec![0; hand_size + 1];
+
+        for _ in 0..self.samples {
+            let mut playout = self.determinize(view);
+
+            // Players who already guessed play to their guess, the others to their estimate
+            let active_players = playout.hands.iter().filter(|h| !h.is_empty()).count();
+            for p in 0..playout.hands.len() {
+                if p != player_id && view.seats[p].guessed {
+                    continue;
+                }
+
+                let unseen: Vec<Card> = playout
+                    .hands
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != p)
+                    .flat_map(|(_, hand)| hand.iter().copied())
+                    .collect();
+                playout.guesses[p] = estimate_tricks(
+                    &playout.card_order,
+                    &playout.hands[p],
+                    &unseen,
+                    active_players.saturating_sub(1),
+                );
+            }
+
+            playout.run();
+            outcomes[playout.wins[player_id].min(hand_size)] += 1;
+        }
+
+        let allowed_guesses = allowed_guesses(view);
+        let most_likely = allowed_guesses
+            .iter()
+            .copied()
+            .max_by_key(|guess| outcomes[*guess]);
+
+        match most_likely {
+            Some(guess) => guess,
+            None => closest_allowed_guess(&allowed_guesses, 0),
+        }
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        let player_id = view.player_id;
+        let guess = view.own_seat().guess;
+        let heuristic_card = pick_card(
+            &CardOrder::new(view),
+            own_hand(view),
+            &view.played_cards,
+            player_id,
+            guess,
+            view.own_seat().wins,
+        );
+
+        // The heuristic choice goes first so it wins any tie in the scores
+        let mut candidates = vec![heuristic_card];
+        candidates.extend(
+            own_hand(view)
+                .iter()
+                .filter(|card| **card != heuristic_card),
+        );
+
+        let mut best_card = heuristic_card;
+        let mut best_score = 0;
+        for candidate in candidates {
+            let mut score = 0;
+            for _ in 0..self.samples {
+                let mut playout = self.determinize(view);
+                playout.play(candidate);
+                playout.run();
+
+                if playout.wins[player_id] == guess {
+                    score += 1;
+                }
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_card = candidate;
+            }
+        }
+
+        best_card
+    }
+}
+
+impl Playout {
+    fn run(&mut self) {
+        while self.hands.iter().any(|h| !h.is_empty()) || !self.played_cards.is_empty() {
+            if self.trick_finished() {
+                self.finish_trick();
+                continue;
+            }
+
+            let player = self.player_turn;
+            let card = pick_card(
+                &self.card_order,
+                &self.hands[player],
+                &self.played_cards,
+                player,
+                self.guesses[player],
+                self.wins[player],
+            );
+            self.play(card);
+        }
+    }
+
+    fn play(&mut self, card: Card) {
+        let player = self.player_turn;
+        if let Some(index) = self.hands[player].iter().position(|c| *c == card) {
+            self.hands[player].remove(index);
+        }
+
+        self.card_order.insert(
+            &mut self.played_cards,
+            PlayedCard {
+                player_id: player,
+                card,
+                cancelled: false,
+            },
+        );
+
+        if let Some(next_player) = self.next_player_to_play() {
+            self.player_turn = next_player;
+        }
+    }
+
+    fn trick_finished(&self) -> bool {
+        self.next_player_to_play().is_none()
+    }
+
+    fn next_player_to_play(&self) -> Option<usize> {
+        let player_count = self.hands.len();
+
+        (1..=player_count)
+            .map(|offset| (self.player_turn + offset) % player_count)
+            .find(|p| !self.hands[*p].is_empty() && !has_played(&self.played_cards, *p))
+    }
+
+    fn finish_trick(&mut self) {
+        // Who led the trick is not tracked, so a fully cancelled trick is led by the last player
+        match self.card_order.trick_winner(&self.played_cards) {
+            Some(winner) => {
+                self.wins[winner] += 1 + self.carried_tricks;
+                self.carried_tricks = 0;
+                self.player_turn = winner;
+            }
+            None if self.card_order.carries_over() => self.carried_tricks += 1,
+            None => (),
+        }
+
+        self.played_cards.clear();
+
+        if self.hands[self.player_turn].is_empty()
+            && let Some(next_player) = self.next_player_to_play()
+        {
+            self.player_turn = next_player;
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use rand::{Rng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::game_logic::{common::Card, view::PlayerView};
+
+use super::{Bot, allowed_guesses, own_hand};
+
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng }
+    }
+}
+
+impl Bot for RandomBot {
+    fn choose_guess(&mut self, view: &PlayerView) -> usize {
+        let allowed_guesses = allowed_guesses(view);
+
+        allowed_guesses[self.rng.random_range(0..allowed_guesses.len())]
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        *own_hand(view)
+            .choose(&mut self.rng)
+            .expect("Bot has no cards to play")
+    }
+}
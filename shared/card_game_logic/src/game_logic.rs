@@ -1,12 +1,23 @@
-use common::{Card, CardPlayedResult, PlayedCard};
+use std::fmt;
+
+use common::{Card, CardPlayedResult, GameOutcome, PlayedCard};
+use events::GameEvent;
+use history::{MatchRecord, PlayerStats, TrickRecord};
+use rules::{RoundSchedule, RuleSet, TrumpRule};
+use view::{GamePhase, PlayerView, SeatView};
 
 pub mod common;
+pub mod events;
+pub mod history;
 pub mod local;
 pub mod online;
+pub mod rules;
+pub mod view;
 
 pub struct GameSettings {
     pub player_count: usize,
     pub initial_card_count: usize,
+    pub rules: RuleSet,
 }
 
 impl Default for GameSettings {
@@ -14,22 +25,164 @@ impl Default for GameSettings {
         Self {
             player_count: 3,
             initial_card_count: 3,
+            rules: RuleSet::default(),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingsError {
+    NotEnoughPlayers,
+    NoStartingCards,
+    NoStartingLives,
+    EmptyLargestHand,
+    NoDecks,
+    InvalidDeck,
+    NotEnoughCards { needed: usize, available: usize },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::NotEnoughPlayers => write!(f, "At least two players are needed"),
+            SettingsError::NoStartingCards => {
+                write!(f, "Players need to start with at least one card")
+            }
+            SettingsError::NoStartingLives => {
+                write!(f, "Players need to start with at least one life")
+            }
+            SettingsError::EmptyLargestHand => {
+                write!(f, "The largest hand needs at least one card")
+            }
+            SettingsError::NoDecks => write!(f, "At least one deck is needed"),
+            SettingsError::InvalidDeck => {
+                write!(f, "The deck needs at least one rank and no repeated ranks")
+            }
+            SettingsError::NotEnoughCards { needed, available } => write!(
+                f,
+                "The table needs {} cards, but the deck only has {}",
+                needed, available
+            ),
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.player_count < 2 {
+            return Err(SettingsError::NotEnoughPlayers);
+        }
+
+        if self.initial_card_count == 0 {
+            return Err(SettingsError::NoStartingCards);
+        }
+
+        if self.rules.starting_lives == Some(0) {
+            return Err(SettingsError::NoStartingLives);
+        }
+
+        if self.rules.deck_count == 0 {
+            return Err(SettingsError::NoDecks);
+        }
+
+        if !self.rules.deck.is_valid() {
+            return Err(SettingsError::InvalidDeck);
+        }
+
+        let max_hand_size = match self.rules.schedule {
+            RoundSchedule::ShrinkingHands => self.initial_card_count,
+            RoundSchedule::AscendingDescending { max_hand_size } => max_hand_size,
+        };
+
+        if max_hand_size == 0 {
+            return Err(SettingsError::EmptyLargestHand);
+        }
+
+        let vira_cards = match self.rules.trump {
+            TrumpRule::Vira => 1,
+            TrumpRule::None => 0,
+        };
+        let needed = max_hand_size * self.player_count + vira_cards;
+        let available = self.rules.deck_size();
+
+        if needed > available {
+            return Err(SettingsError::NotEnoughCards { needed, available });
+        }
+
+        Ok(())
+    }
+}
+
 pub trait GameLogic {
-    fn start_match(&mut self, inital_card_count: usize) -> CardPlayedResult;
+    // Starts the game, as long as the table and the deck work with the card count
+    fn start_match(&mut self, inital_card_count: usize) -> Result<CardPlayedResult, SettingsError>;
     fn set_guess(&mut self, player_id: usize, guess: usize) -> Result<(), String>;
     fn play_card(&mut self, player_id: usize, card: &Card) -> Result<CardPlayedResult, String>;
     fn get_player_cards(&self, player_id: usize) -> &Vec<Card>;
     fn get_player_card_count(&self, player_id: usize) -> usize;
+    fn get_hand_size(&self, player_id: usize) -> usize;
     fn get_player_turn(&self) -> usize;
     fn get_player_guess(&self, player_id: usize) -> usize;
+    // Whether the guess of the seat is final for the current match, rather than a placeholder
+    fn has_guessed(&self, player_id: usize) -> bool;
     fn get_player_wins(&self, player_id: usize) -> usize;
-    fn get_winner(&self) -> usize;
+    fn get_outcome(&self) -> Option<GameOutcome>;
     fn get_game_over(&self) -> bool;
     fn get_played_cards(&self) -> &Vec<PlayedCard>;
     fn get_guessing_round(&self) -> bool;
     fn get_player_count(&self) -> usize;
+    fn get_forbidden_guess(&self) -> Option<usize>;
+    fn get_rules(&self) -> &RuleSet;
+    fn get_vira(&self) -> Option<Card>;
+    fn is_hand_visible_to(&self, owner_id: usize, viewer_id: usize) -> bool;
+    fn get_player_lives(&self, player_id: usize) -> usize;
+    fn get_round_number(&self) -> usize;
+    fn get_total_rounds(&self) -> Option<usize>;
+    // Events emitted since the last call, oldest first
+    fn take_events(&mut self) -> Vec<GameEvent>;
+    // Tricks finished in the current match, oldest first
+    fn get_trick_history(&self) -> &Vec<TrickRecord>;
+    // Every finished match of the game, oldest first
+    fn get_match_history(&self) -> &Vec<MatchRecord>;
+
+    fn get_player_stats(&self, player_id: usize) -> PlayerStats {
+        PlayerStats::from_matches(self.get_match_history(), player_id)
+    }
+
+    fn get_player_view(&self, player_id: usize) -> PlayerView {
+        let phase = if self.get_game_over() {
+            GamePhase::GameOver
+        } else if self.get_guessing_round() {
+            GamePhase::Guessing
+        } else {
+            GamePhase::Playing
+        };
+
+        let seats = (0..self.get_player_count())
+            .map(|seat_id| SeatView {
+                hand_size: self.get_hand_size(seat_id),
+                dealt_count: self.get_player_card_count(seat_id),
+                guess: self.get_player_guess(seat_id),
+                guessed: self.has_guessed(seat_id),
+                wins: self.get_player_wins(seat_id),
+                lives: self.get_player_lives(seat_id),
+                hand: self
+                    .is_hand_visible_to(seat_id, player_id)
+                    .then(|| self.get_player_cards(seat_id).clone()),
+            })
+            .collect();
+
+        PlayerView {
+            player_id,
+            phase,
+            player_turn: self.get_player_turn(),
+            round_number: self.get_round_number(),
+            vira: self.get_vira(),
+            rules: self.get_rules().clone(),
+            forbidden_guess: self.get_forbidden_guess(),
+            played_cards: self.get_played_cards().clone(),
+            seats,
+            outcome: self.get_outcome(),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-
+use std::str::FromStr;
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum Suit {
@@ -38,10 +38,49 @@ impl PartialOrd for Card {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+// Suits and ranks are sent over the network with their Debug names, e.g. "Hearts" and "Ace"
+impl FromStr for Suit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Clubs" => Ok(Suit::Clubs),
+            "Hearts" => Ok(Suit::Hearts),
+            "Diamonds" => Ok(Suit::Diamonds),
+            "Spades" => Ok(Suit::Spades),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Ace" => Ok(Rank::Ace),
+            "Two" => Ok(Rank::Two),
+            "Three" => Ok(Rank::Three),
+            "Four" => Ok(Rank::Four),
+            "Five" => Ok(Rank::Five),
+            "Six" => Ok(Rank::Six),
+            "Seven" => Ok(Rank::Seven),
+            "Eight" => Ok(Rank::Eight),
+            "Nine" => Ok(Rank::Nine),
+            "Ten" => Ok(Rank::Ten),
+            "Jack" => Ok(Rank::Jack),
+            "Queen" => Ok(Rank::Queen),
+            "King" => Ok(Rank::King),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PlayedCard{
     pub player_id: usize,
     pub card: Card,
+    pub cancelled: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
@@ -51,4 +90,210 @@ pub enum CardPlayedResult {
     NextMatch,
     GameOver,
     WaitUpdate,
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameOutcome {
+    Winner(usize),
+    Draw(Vec<usize>),
+    Abandoned,
+}
+
+pub fn create_deck() -> Vec<Card> {
+    let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+    let ranks = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    // Collect all combinations of suits and ranks into an array
+    suits
+        .iter()
+        .flat_map(|&suit| ranks.iter().map(move |&rank| Card(suit, rank)))
+        .collect()
+}
+
+// Which cards exist in the deck and how their ranks compare, weakest rank first
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeckSpec {
+    pub rank_order: Vec<Rank>,
+}
+
+impl DeckSpec {
+    // The order the game has always used, with the ace as the lowest card
+    pub fn classic() -> Self {
+        Self::custom(vec![
+            Rank::Ace,
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Jack,
+            Rank::Queen,
+            Rank::King,
+        ])
+    }
+
+    pub fn poker() -> Self {
+        Self::custom(vec![
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Eight,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Jack,
+            Rank::Queen,
+            Rank::King,
+            Rank::Ace,
+        ])
+    }
+
+    // 40-card Spanish/Brazilian deck, without eights, nines and tens
+    pub fn truco() -> Self {
+        Self::custom(vec![
+            Rank::Four,
+            Rank::Five,
+            Rank::Six,
+            Rank::Seven,
+            Rank::Queen,
+            Rank::Jack,
+            Rank::King,
+            Rank::Ace,
+            Rank::Two,
+            Rank::Three,
+        ])
+    }
+
+    pub fn custom(rank_order: Vec<Rank>) -> Self {
+        Self { rank_order }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.rank_order.is_empty()
+            && self
+                .rank_order
+                .iter()
+                .enumerate()
+                .all(|(index, rank)| !self.rank_order[..index].contains(rank))
+    }
+
+    pub fn has_rank(&self, rank: Rank) -> bool {
+        self.rank_order.contains(&rank)
+    }
+
+    pub fn rank_strength(&self, rank: Rank) -> Option<usize> {
+        self.rank_order.iter().position(|r| *r == rank)
+    }
+
+    // Ties between ranks are broken by suit, like the ordering on Card
+    pub fn compare_cards(&self, a: &Card, b: &Card) -> std::cmp::Ordering {
+        self.rank_strength(a.1)
+            .cmp(&self.rank_strength(b.1))
+            .then_with(|| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    // The rank after the given one, wrapping around from the strongest to the weakest
+    pub fn next_rank(&self, rank: Rank) -> Rank {
+        match self.rank_strength(rank) {
+            Some(index) => self.rank_order[(index + 1) % self.rank_order.len()],
+            None => rank,
+        }
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        create_deck()
+            .into_iter()
+            .filter(|card| self.has_rank(card.1))
+            .collect()
+    }
+
+    // Ranks as printed on the cards, weakest first, e.g. "4,5,6,7,Q,J,K,A,2,3"
+    pub fn from_rank_list(value: &str) -> Result<Self, String> {
+        let deck = Self::custom(
+            value
+                .split(',')
+                .map(|rank| {
+                    let rank = rank.trim().to_ascii_uppercase();
+                    rank_from_symbol(&rank).ok_or_else(|| format!("Unknown rank: {}", rank))
+                })
+                .collect::<Result<_, _>>()?,
+        );
+
+        if !deck.is_valid() {
+            return Err(String::from("Every rank can only be listed once"));
+        }
+
+        Ok(deck)
+    }
+
+    pub fn rank_list(&self) -> String {
+        self.rank_order
+            .iter()
+            .map(|rank| rank_symbol(*rank))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn rank_symbol(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Ace => "A",
+        Rank::Two => "2",
+        Rank::Three => "3",
+        Rank::Four => "4",
+        Rank::Five => "5",
+        Rank::Six => "6",
+        Rank::Seven => "7",
+        Rank::Eight => "8",
+        Rank::Nine => "9",
+        Rank::Ten => "10",
+        Rank::Jack => "J",
+        Rank::Queen => "Q",
+        Rank::King => "K",
+    }
+}
+
+fn rank_from_symbol(symbol: &str) -> Option<Rank> {
+    match symbol {
+        "A" => Some(Rank::Ace),
+        "2" => Some(Rank::Two),
+        "3" => Some(Rank::Three),
+        "4" => Some(Rank::Four),
+        "5" => Some(Rank::Five),
+        "6" => Some(Rank::Six),
+        "7" => Some(Rank::Seven),
+        "8" => Some(Rank::Eight),
+        "9" => Some(Rank::Nine),
+        "10" => Some(Rank::Ten),
+        "J" => Some(Rank::Jack),
+        "Q" => Some(Rank::Queen),
+        "K" => Some(Rank::King),
+        _ => None,
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
@@ -0,0 +1,45 @@
+use super::common::{Card, GameOutcome};
+
+// Everything that changes the game state, in the order it happened
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    MatchStarted {
+        round_number: usize,
+        hand_sizes: Vec<usize>,
+        vira: Option<Card>,
+    },
+    GuessMade {
+        player_id: usize,
+        guess: usize,
+    },
+    GuessingFinished,
+    CardPlayed {
+        player_id: usize,
+        card: Card,
+    },
+    TurnChanged {
+        player_id: usize,
+    },
+    // tricks_won includes the tricks carried over from cancelled ones
+    TrickFinished {
+        winner: Option<usize>,
+        tricks_won: usize,
+    },
+    LivesLost {
+        player_id: usize,
+        lives_lost: usize,
+        lives_left: usize,
+    },
+    PlayerEliminated {
+        player_id: usize,
+    },
+    GameOver {
+        outcome: GameOutcome,
+    },
+    // An undo or redo replaced the whole state, so anything derived from it must be rebuilt
+    HistoryRestored,
+}
+
+pub trait GameObserver {
+    fn on_event(&mut self, event: &GameEvent);
+}
@@ -0,0 +1,62 @@
+use super::common::PlayedCard;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrickRecord {
+    // In the order they were played, starting with the lead
+    pub cards: Vec<PlayedCard>,
+    pub lead: usize,
+    pub winner: Option<usize>,
+    // Includes the tricks carried over from cancelled ones
+    pub tricks_won: usize,
+}
+
+// Indexed by player, players without cards in the match have a hand size of zero
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchRecord {
+    pub round_number: usize,
+    pub hand_sizes: Vec<usize>,
+    pub guesses: Vec<usize>,
+    pub wins: Vec<usize>,
+    pub lives_lost: Vec<usize>,
+    pub tricks: Vec<TrickRecord>,
+}
+
+impl MatchRecord {
+    pub fn played(&self, player_id: usize) -> bool {
+        self.hand_sizes[player_id] > 0
+    }
+
+    pub fn guess_hit(&self, player_id: usize) -> bool {
+        self.played(player_id) && self.guesses[player_id] == self.wins[player_id]
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerStats {
+    pub matches_played: usize,
+    pub guesses_hit: usize,
+    pub tricks_won: usize,
+    pub lives_lost: usize,
+}
+
+impl PlayerStats {
+    pub fn from_matches(matches: &[MatchRecord], player_id: usize) -> Self {
+        matches
+            .iter()
+            .filter(|record| record.played(player_id))
+            .fold(Self::default(), |stats, record| Self {
+                matches_played: stats.matches_played + 1,
+                guesses_hit: stats.guesses_hit + record.guess_hit(player_id) as usize,
+                tricks_won: stats.tricks_won + record.wins[player_id],
+                lives_lost: stats.lives_lost + record.lives_lost[player_id],
+            })
+    }
+
+    pub fn guess_hit_rate(&self) -> f64 {
+        self.guesses_hit as f64 / self.matches_played.max(1) as f64
+    }
+
+    pub fn lives_lost_per_match(&self) -> f64 {
+        self.lives_lost as f64 / self.matches_played.max(1) as f64
+    }
+}
@@ -1,6 +1,11 @@
+use rand::{SeedableRng, rngs::StdRng};
+
 use super::{
-    GameLogic,
-    common::{Card, CardPlayedResult, PlayedCard, Rank, Suit},
+    GameLogic, GameSettings, SettingsError,
+    common::{Card, CardPlayedResult, GameOutcome, PlayedCard},
+    events::{GameEvent, GameObserver},
+    history::{MatchRecord, TrickRecord},
+    rules::{CancelledTrick, RuleSet, TieRule, TrumpRule, UndoRule},
 };
 
 pub struct LocalGameLogic {
@@ -12,39 +17,256 @@ pub struct LocalGameLogic {
     pub deck: Vec<Card>,
     pub guessing_round: bool,
     pub guesses: Vec<usize>,
+    pub rules: RuleSet,
+    pub vira: Option<Card>,
+    pub lives: Vec<usize>,
+    initial_card_count: usize,
+    round_number: usize,
+    abandoned: bool,
     last_to_guess: usize,
     starting_turn: usize,
     wins: Vec<usize>,
+    carried_tricks: usize,
+    rng: StdRng,
+    events: Vec<GameEvent>,
+    observers: Vec<Box<dyn GameObserver + Send + Sync>>,
+    trick_history: Vec<TrickRecord>,
+    match_history: Vec<MatchRecord>,
+    history: Vec<Snapshot>,
+    redo_history: Vec<Snapshot>,
+}
+
+// Everything a guess or a card play can change
+#[derive(Clone)]
+struct Snapshot {
+    player_turn: usize,
+    player_cards: Vec<Vec<Card>>,
+    player_card_count: Vec<usize>,
+    game_over: bool,
+    cards_played: Vec<PlayedCard>,
+    deck: Vec<Card>,
+    guessing_round: bool,
+    guesses: Vec<usize>,
+    vira: Option<Card>,
+    lives: Vec<usize>,
+    round_number: usize,
+    last_to_guess: usize,
+    starting_turn: usize,
+    wins: Vec<usize>,
+    carried_tricks: usize,
+    trick_history: Vec<TrickRecord>,
 }
 
 impl LocalGameLogic {
-    pub fn init(&mut self, player_count: usize) {
+    pub fn init(&mut self, player_count: usize, rules: RuleSet) {
+        self.rules = rules;
         self.player_turn = 0;
         self.player_card_count = vec![0; player_count];
+        self.lives = vec![0; player_count];
+        self.round_number = 0;
+        self.abandoned = false;
         self.game_over = false;
         self.wins = vec![0; player_count];
         self.player_cards = vec![Vec::new(); player_count];
         self.cards_played = Vec::new();
+        self.trick_history.clear();
+        self.match_history.clear();
+        self.events.clear();
+        self.history.clear();
+        self.redo_history.clear();
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Ends the game without a result, e.g. when a player quits mid-game
+    pub fn abandon(&mut self) {
+        self.game_over = true;
+        self.abandoned = true;
+        self.emit(GameEvent::GameOver {
+            outcome: GameOutcome::Abandoned,
+        });
+    }
+
+    // Observers are called as soon as an event happens, while take_events hands them out in batches
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver + Send + Sync>) {
+        self.observers.push(observer);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_history.is_empty()
+    }
+
+    pub fn undo(&mut self) -> Result<(), String> {
+        let snapshot = self.history.pop().ok_or("Nothing to undo")?;
+        self.redo_history.push(self.snapshot());
+        self.restore(snapshot);
+
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), String> {
+        let snapshot = self.redo_history.pop().ok_or("Nothing to redo")?;
+        self.history.push(self.snapshot());
+        self.restore(snapshot);
+
+        Ok(())
+    }
+
+    // Called right before a guess or card play changes the state
+    fn record_history(&mut self) {
+        if self.rules.undo == UndoRule::Disabled {
+            return;
+        }
+
+        let snapshot = self.snapshot();
+        self.history.push(snapshot);
+        self.redo_history.clear();
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.redo_history.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            player_turn: self.player_turn,
+            player_cards: self.player_cards.clone(),
+            player_card_count: self.player_card_count.clone(),
+            game_over: self.game_over,
+            cards_played: self.cards_played.clone(),
+            deck: self.deck.clone(),
+            guessing_round: self.guessing_round,
+            guesses: self.guesses.clone(),
+            vira: self.vira,
+            lives: self.lives.clone(),
+            round_number: self.round_number,
+            last_to_guess: self.last_to_guess,
+            starting_turn: self.starting_turn,
+            wins: self.wins.clone(),
+            carried_tricks: self.carried_tricks,
+            trick_history: self.trick_history.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.player_turn = snapshot.player_turn;
+        self.player_cards = snapshot.player_cards;
+        self.player_card_count = snapshot.player_card_count;
+        self.game_over = snapshot.game_over;
+        self.cards_played = snapshot.cards_played;
+        self.deck = snapshot.deck;
+        self.guessing_round = snapshot.guessing_round;
+        self.guesses = snapshot.guesses;
+        self.vira = snapshot.vira;
+        self.lives = snapshot.lives;
+        self.round_number = snapshot.round_number;
+        self.last_to_guess = snapshot.last_to_guess;
+        self.starting_turn = snapshot.starting_turn;
+        self.wins = snapshot.wins;
+        self.carried_tricks = snapshot.carried_tricks;
+        self.trick_history = snapshot.trick_history;
+
+        self.emit(GameEvent::HistoryRestored);
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&event);
+        }
+
+        self.events.push(event);
+    }
+
+    fn emit_turn_change(&mut self, previous_turn: usize) {
+        if self.player_turn != previous_turn && !self.game_over {
+            self.emit(GameEvent::TurnChanged {
+                player_id: self.player_turn,
+            });
+        }
+    }
+
+    // Guesses and plays need a dealt match, and the round number only moves once one is dealt
+    fn check_in_progress(&self) -> Result<(), String> {
+        if self.game_over {
+            return Err(String::from("Game is over"));
+        }
+
+        if self.round_number == 0 {
+            return Err(String::from("Match has not started"));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_blind_round(&self) -> bool {
+        self.rules.blind_one_card_round && self.player_card_count.iter().max() == Some(&1)
     }
 
     fn start_match(&mut self) -> CardPlayedResult {
-        if self.player_card_count.iter().filter(|c| **c > 0).count() == 1 {
+        // Nothing from a finished match can be taken back
+        self.clear_history();
+
+        let schedule_finished = self
+            .rules
+            .schedule
+            .total_rounds()
+            .is_some_and(|total_rounds| self.round_number >= total_rounds);
+
+        if self.lives.iter().filter(|l| **l > 0).count() <= 1 || schedule_finished {
             self.game_over = true;
+            if let Some(outcome) = GameLogic::get_outcome(self) {
+                self.emit(GameEvent::GameOver { outcome });
+            }
             return CardPlayedResult::GameOver;
         }
+
+        self.round_number += 1;
+        let hand_size = self.rules.schedule.hand_size(self.round_number);
+        for (card_count, lives) in self.player_card_count.iter_mut().zip(&self.lives) {
+            *card_count = if *lives == 0 {
+                0
+            } else {
+                hand_size.unwrap_or((*lives).min(self.initial_card_count))
+            };
+        }
+
+        while self.player_card_count[self.player_turn] == 0 {
+            self.player_turn = (self.player_turn + 1) % self.player_card_count.len();
+        }
+
         self.starting_turn = self.player_turn;
-        self.deck = create_deck();
-        shuffle_deck(&mut self.deck);
+        self.deck = self.rules.build_deck();
+        shuffle_deck(&mut self.deck, &mut self.rng);
         distribute_cards(self);
+        self.vira = match self.rules.trump {
+            TrumpRule::Vira => self.deck.pop(),
+            TrumpRule::None => None,
+        };
         self.guessing_round = true;
         self.guesses = vec![0; self.player_card_count.len()];
         self.wins = vec![0; self.player_card_count.len()];
-
-        if self.player_turn == 0 {
-            self.last_to_guess = self.player_card_count.len() - 1;
-        } else {
-            self.last_to_guess = self.player_turn - 1;
-        }
+        self.carried_tricks = 0;
+        self.trick_history.clear();
+
+        self.emit(GameEvent::MatchStarted {
+            round_number: self.round_number,
+            hand_sizes: self.player_card_count.clone(),
+            vira: self.vira,
+        });
+
+        // The last guess belongs to the closest seat before the first player that was dealt cards
+        let player_count = self.player_card_count.len();
+        self.last_to_guess = (1..player_count)
+            .map(|offset| (self.player_turn + player_count - offset) % player_count)
+            .find(|p| self.player_card_count[*p] > 0)
+            .unwrap_or(self.player_turn);
 
         return CardPlayedResult::NextMatch;
     }
@@ -61,44 +283,31 @@ impl Default for LocalGameLogic {
             deck: Vec::new(),
             guessing_round: false,
             guesses: Vec::new(),
+            rules: RuleSet::default(),
+            vira: None,
+            lives: Vec::new(),
+            initial_card_count: 0,
+            round_number: 0,
+            abandoned: false,
             last_to_guess: 0,
             starting_turn: 0,
             wins: Vec::new(),
+            carried_tricks: 0,
+            rng: StdRng::from_os_rng(),
+            events: Vec::new(),
+            observers: Vec::new(),
+            trick_history: Vec::new(),
+            match_history: Vec::new(),
+            history: Vec::new(),
+            redo_history: Vec::new(),
         }
     }
 }
 
-fn create_deck() -> Vec<Card> {
-    let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
-    let ranks = [
-        Rank::Two,
-        Rank::Three,
-        Rank::Four,
-        Rank::Five,
-        Rank::Six,
-        Rank::Seven,
-        Rank::Eight,
-        Rank::Nine,
-        Rank::Ten,
-        Rank::Jack,
-        Rank::Queen,
-        Rank::King,
-        Rank::Ace,
-    ];
-
-    // Collect all combinations of suits and ranks into an array
-    return suits
-        .iter()
-        .flat_map(|&suit| ranks.iter().map(move |&rank| Card(suit, rank)))
-        .collect();
-}
-
-fn shuffle_deck(deck: &mut Vec<Card>) {
-    use rand::rng;
+fn shuffle_deck(deck: &mut Vec<Card>, rng: &mut StdRng) {
     use rand::seq::SliceRandom;
 
-    let mut rng = rng();
-    deck.shuffle(&mut rng);
+    deck.shuffle(rng);
 }
 
 fn start_playing_round(game_logic: &mut LocalGameLogic) {
@@ -112,6 +321,11 @@ fn start_playing_round(game_logic: &mut LocalGameLogic) {
     game_logic.starting_turn = game_logic.player_turn;
 }
 
+fn finish_guessing(game_logic: &mut LocalGameLogic) {
+    start_playing_round(game_logic);
+    game_logic.emit(GameEvent::GuessingFinished);
+}
+
 fn next_player_turn(game_logic: &mut LocalGameLogic) -> CardPlayedResult {
     game_logic.player_turn =
         (game_logic.player_turn + 1) % game_logic.player_card_count.len() as usize;
@@ -131,27 +345,62 @@ fn next_player_turn(game_logic: &mut LocalGameLogic) -> CardPlayedResult {
 }
 
 fn check_turn_winner(game_logic: &mut LocalGameLogic) -> CardPlayedResult {
-    let winning_player = game_logic.cards_played.last().unwrap().player_id;
+    let winner = game_logic.rules.trick_winner(&game_logic.cards_played);
+    let tricks_won = match winner {
+        Some(winning_player) => {
+            let tricks_won = 1 + game_logic.carried_tricks;
+            game_logic.wins[winning_player] += tricks_won;
+            game_logic.carried_tricks = 0;
+            game_logic.player_turn = winning_player;
+            tricks_won
+        }
+        None => {
+            // Every card was cancelled, the player who led the trick leads again
+            if game_logic.rules.tie == TieRule::Cancel(CancelledTrick::CarryOver) {
+                game_logic.carried_tricks += 1;
+            }
+            game_logic.player_turn = game_logic.starting_turn;
+            0
+        }
+    };
 
-    game_logic.wins[winning_player] += 1;
-    game_logic.player_turn = winning_player;
+    record_trick(game_logic, winner, tricks_won);
+    game_logic.emit(GameEvent::TrickFinished { winner, tricks_won });
+
+    if game_logic.rules.undo == UndoRule::CurrentTrick {
+        game_logic.clear_history();
+    }
 
     let has_cards_to_play = game_logic.player_cards.iter().any(|c| c.len() > 0);
     while game_logic.player_cards[game_logic.player_turn].len() == 0 && has_cards_to_play {
         game_logic.player_turn = (game_logic.player_turn + 1) % game_logic.player_card_count.len();
     }
 
-    println!("Next player is {}", game_logic.player_turn);
-
     game_logic.cards_played.clear();
     check_match_finished(game_logic)
 }
 
+// The played cards are kept sorted by strength, everyone plays once per trick so seat order
+// starting from the lead gives back the order they were played in
+fn record_trick(game_logic: &mut LocalGameLogic, winner: Option<usize>, tricks_won: usize) {
+    let lead = game_logic.starting_turn;
+    let player_count = game_logic.player_card_count.len();
+    let mut cards = game_logic.cards_played.clone();
+    cards.sort_by_key(|played| (played.player_id + player_count - lead) % player_count);
+
+    game_logic.trick_history.push(TrickRecord {
+        cards,
+        lead,
+        winner,
+        tricks_won,
+    });
+}
+
 fn check_match_finished(game_logic: &mut LocalGameLogic) -> CardPlayedResult {
     let match_finished = game_logic.player_cards.iter().all(|c| c.len() == 0);
 
     if match_finished {
-        remove_cards_from_players(game_logic);
+        remove_lives_from_players(game_logic);
         return game_logic.start_match();
     } else {
         start_playing_round(game_logic);
@@ -159,15 +408,45 @@ fn check_match_finished(game_logic: &mut LocalGameLogic) -> CardPlayedResult {
     }
 }
 
-fn remove_cards_from_players(game_logic: &mut LocalGameLogic) {
+fn remove_lives_from_players(game_logic: &mut LocalGameLogic) {
+    let lives_at_start = game_logic.lives.clone();
+
     for player_id in 0..game_logic.player_cards.len() {
         let wins = game_logic.wins[player_id];
         let guess = game_logic.guesses[player_id];
-        if wins != guess {
-            game_logic.player_card_count[player_id] -= 1;
+        if game_logic.player_card_count[player_id] > 0 {
+            let lives_before = game_logic.lives[player_id];
+            let lives_lost = game_logic.rules.life_loss.lives_lost(guess, wins);
+            let lives_left = lives_before.saturating_sub(lives_lost);
+            game_logic.lives[player_id] = lives_left;
+
+            if lives_lost > 0 {
+                game_logic.emit(GameEvent::LivesLost {
+                    player_id,
+                    lives_lost: lives_before - lives_left,
+                    lives_left,
+                });
+            }
+
+            if lives_before > 0 && lives_left == 0 {
+                game_logic.emit(GameEvent::PlayerEliminated { player_id });
+            }
         }
     }
 
+    game_logic.match_history.push(MatchRecord {
+        round_number: game_logic.round_number,
+        hand_sizes: game_logic.player_card_count.clone(),
+        guesses: game_logic.guesses.clone(),
+        wins: game_logic.wins.clone(),
+        lives_lost: lives_at_start
+            .iter()
+            .zip(&game_logic.lives)
+            .map(|(before, after)| before - after)
+            .collect(),
+        tricks: game_logic.trick_history.clone(),
+    });
+
     game_logic.cards_played.clear();
 }
 
@@ -180,61 +459,74 @@ fn distribute_cards(game_logic: &mut LocalGameLogic) {
 }
 
 fn push_played_card(game_logic: &mut LocalGameLogic, card: &PlayedCard) {
-    match game_logic.cards_played.pop() {
-        Some(last_card) => {
-            if last_card.card > card.card {
-                game_logic.cards_played.push(*card);
-                game_logic.cards_played.push(last_card);
-            } else {
-                game_logic.cards_played.push(last_card);
-                game_logic.cards_played.push(*card);
-            }
-        }
-        _ => game_logic.cards_played.push(*card),
-    }
+    game_logic
+        .rules
+        .insert_played_card(&mut game_logic.cards_played, *card, game_logic.vira);
 }
 
 impl GameLogic for LocalGameLogic {
-    fn start_match(&mut self, initial_card_count: usize) -> CardPlayedResult {
-        self.player_card_count = vec![initial_card_count; self.player_card_count.len()];
+    fn start_match(
+        &mut self,
+        initial_card_count: usize,
+    ) -> Result<CardPlayedResult, SettingsError> {
+        // Dealing more cards than the deck holds would panic, so settings are checked here too
+        GameSettings {
+            player_count: self.player_card_count.len(),
+            initial_card_count,
+            rules: self.rules.clone(),
+        }
+        .validate()?;
 
-        self.start_match()
+        let starting_lives = self.rules.starting_lives.unwrap_or(initial_card_count);
+        self.lives = vec![starting_lives; self.player_card_count.len()];
+        self.initial_card_count = initial_card_count;
+        self.round_number = 0;
+
+        let previous_turn = self.player_turn;
+        let result = self.start_match();
+        self.emit_turn_change(previous_turn);
+
+        Ok(result)
     }
 
     fn set_guess(&mut self, player_id: usize, guess: usize) -> Result<(), String> {
-        if self.game_over {
-            return Err(String::from("Game is over"));
-        }
+        self.check_in_progress()?;
 
-        let total_guesses = self.guesses.iter().sum::<usize>();
-        let max_cards = self.player_card_count.iter().max().unwrap_or(&0);
         let next_player = (self.player_turn + 1) % self.player_card_count.len();
         if self.player_turn == player_id {
-            if total_guesses + guess == *max_cards && next_player == self.starting_turn {
+            if guess > self.player_cards[player_id].len() {
+                return Err(String::from(
+                    "You cannot guess more tricks than cards in hand",
+                ));
+            }
+
+            if self.get_forbidden_guess() == Some(guess) {
                 return Err(String::from(
                     "You cannot guess the same number of cards as the maximum cards in hand",
                 ));
             }
 
+            self.record_history();
             self.guesses[player_id] = guess;
             self.player_turn = next_player;
+            self.emit(GameEvent::GuessMade { player_id, guess });
 
             while self.player_cards[self.player_turn].len() == 0 {
                 self.player_turn = (self.player_turn + 1) % self.player_card_count.len();
             }
 
             if self.player_turn == self.starting_turn {
-                start_playing_round(self);
+                finish_guessing(self);
             }
+
+            self.emit_turn_change(player_id);
         }
 
         return Ok(());
     }
 
     fn play_card(&mut self, player_id: usize, card: &Card) -> Result<CardPlayedResult, String> {
-        if self.game_over {
-            return Err(String::from("Game is over"));
-        }
+        self.check_in_progress()?;
 
         if self.guessing_round || self.player_turn != player_id {
             return Err(String::from("Guessing round or not your turn"));
@@ -245,6 +537,7 @@ impl GameLogic for LocalGameLogic {
             .position(|c| *c == *card)
         {
             Some(index) => {
+                self.record_history();
                 self.player_cards[player_id as usize].remove(index);
 
                 push_played_card(
@@ -252,10 +545,18 @@ impl GameLogic for LocalGameLogic {
                     &PlayedCard {
                         player_id: player_id as usize,
                         card: *card,
+                        cancelled: false,
                     },
                 );
+                self.emit(GameEvent::CardPlayed {
+                    player_id,
+                    card: *card,
+                });
+
+                let result = next_player_turn(self);
+                self.emit_turn_change(player_id);
 
-                Ok(next_player_turn(self))
+                Ok(result)
             }
             None => return Err(String::from("Player does not have this card")),
         }
@@ -269,27 +570,63 @@ impl GameLogic for LocalGameLogic {
         self.player_card_count[player_id as usize]
     }
 
+    fn get_hand_size(&self, player_id: usize) -> usize {
+        self.player_cards[player_id].len()
+    }
+
     fn get_player_turn(&self) -> usize {
         self.player_turn
     }
 
+    // Zero until the first match is dealt
     fn get_player_guess(&self, player_id: usize) -> usize {
-        self.guesses[player_id as usize]
+        self.guesses.get(player_id).copied().unwrap_or(0)
+    }
+
+    // Guesses go around the table from the starting seat, up to the seat whose turn it is
+    fn has_guessed(&self, player_id: usize) -> bool {
+        if self.player_card_count.get(player_id).is_none_or(|count| *count == 0) {
+            return false;
+        }
+
+        if !self.guessing_round {
+            return true;
+        }
+
+        let player_count = self.player_card_count.len();
+        let guess_order = |p: usize| (p + player_count - self.starting_turn) % player_count;
+        guess_order(player_id) < guess_order(self.player_turn)
     }
 
     fn get_player_wins(&self, player_id: usize) -> usize {
-        self.wins[player_id]
+        self.wins.get(player_id).copied().unwrap_or(0)
     }
 
-    fn get_winner(&self) -> usize {
-        return self
-            .player_card_count
-            .iter()
-            .filter(|c| **c > 0)
-            .map(|c| *c)
-            .collect::<Vec<usize>>()
-            .pop()
-            .expect("No winner found");
+    fn get_outcome(&self) -> Option<GameOutcome> {
+        if !self.game_over {
+            return None;
+        }
+
+        if self.abandoned {
+            return Some(GameOutcome::Abandoned);
+        }
+
+        let most_lives = self.lives.iter().max().copied().unwrap_or(0);
+        let leaders: Vec<usize> = if most_lives == 0 {
+            // Everyone left was eliminated in the last match, so all of them draw
+            (0..self.player_card_count.len())
+                .filter(|p| self.player_card_count[*p] > 0)
+                .collect()
+        } else {
+            (0..self.lives.len())
+                .filter(|p| self.lives[*p] == most_lives)
+                .collect()
+        };
+
+        match leaders.as_slice() {
+            [winner] => Some(GameOutcome::Winner(*winner)),
+            _ => Some(GameOutcome::Draw(leaders)),
+        }
     }
 
     fn get_game_over(&self) -> bool {
@@ -307,4 +644,55 @@ impl GameLogic for LocalGameLogic {
     fn get_player_count(&self) -> usize {
         self.player_card_count.len()
     }
+
+    fn get_forbidden_guess(&self) -> Option<usize> {
+        if !self.guessing_round || self.player_turn != self.last_to_guess {
+            return None;
+        }
+
+        let total_guesses = self.guesses.iter().sum::<usize>();
+        let max_cards = self.player_card_count.iter().max().unwrap_or(&0);
+        max_cards.checked_sub(total_guesses)
+    }
+
+    fn get_rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    fn get_vira(&self) -> Option<Card> {
+        self.vira
+    }
+
+    fn is_hand_visible_to(&self, owner_id: usize, viewer_id: usize) -> bool {
+        if self.is_blind_round() {
+            // The own card is revealed once the guesses are in
+            owner_id != viewer_id || !self.guessing_round
+        } else {
+            owner_id == viewer_id
+        }
+    }
+
+    fn get_player_lives(&self, player_id: usize) -> usize {
+        self.lives[player_id]
+    }
+
+    fn get_round_number(&self) -> usize {
+        self.round_number
+    }
+
+    fn get_total_rounds(&self) -> Option<usize> {
+        self.rules.schedule.total_rounds()
+    }
+
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn get_trick_history(&self) -> &Vec<TrickRecord> {
+        &self.trick_history
+    }
+
+    fn get_match_history(&self) -> &Vec<MatchRecord> {
+        &self.match_history
+    }
 }
@@ -11,26 +11,42 @@ use crate::{
 };
 
 use super::{
-    common::{Card, CardPlayedResult, PlayedCard}, GameLogic
+    common::{Card, CardPlayedResult, GameOutcome, PlayedCard}, events::GameEvent,
+    history::{MatchRecord, TrickRecord}, rules::RuleSet,
+    view::{GamePhase, PlayerView}, GameLogic, SettingsError
 };
 
+static HIDDEN_HAND: Vec<Card> = Vec::new();
+static NO_TRICKS: Vec<TrickRecord> = Vec::new();
+static NO_MATCHES: Vec<MatchRecord> = Vec::new();
+
 pub struct OnlinePlayerInfo {
     pub player_id: usize,
     pub player_name: String,
     pub player_card_count: usize,
+    pub player_hand_size: usize,
+    pub player_lives: usize,
     pub player_wins: usize,
     pub player_guess: usize,
+    pub player_guessed: bool,
 }
 
 pub struct OnlineGameLogic {
     player_id: Option<usize>,
     is_host: bool,
     player_cards: Vec<Card>,
+    own_hand_hidden: bool,
+    visible_hands: Vec<Vec<Card>>,
     played_cards: Vec<PlayedCard>,
     player_infos: Vec<OnlinePlayerInfo>,
+    rules: RuleSet,
+    vira: Option<Card>,
     player_turn: usize,
+    round_number: usize,
+    forbidden_guess: Option<usize>,
     guessing_round: bool,
     game_over: bool,
+    outcome: Option<GameOutcome>,
     udp_socket: Arc<UdpSocket>,
     server_address: SocketAddr,
     message_queue: Arc<Mutex<Vec<GameMessage>>>,
@@ -60,11 +76,18 @@ impl OnlineGameLogic {
             player_id: None,
             is_host: false,
             player_cards: vec![],
+            own_hand_hidden: false,
+            visible_hands: vec![],
             played_cards: vec![],
             player_infos: vec![],
+            rules: RuleSet::default(),
+            vira: None,
             player_turn: 0,
+            round_number: 0,
+            forbidden_guess: None,
             guessing_round: false,
             game_over: false,
+            outcome: None,
             udp_socket: udp_socket.clone(),
             server_address: server_options::get_server_addr(),
             message_queue: Arc::new(Mutex::new(vec![])),
@@ -76,7 +99,8 @@ impl OnlineGameLogic {
         let message_queue_for_listener = game_logic.message_queue.clone();
         game_logic.listener_thread = Some(thread::spawn(move || {
             loop {
-                let mut buf = [0 as u8; 1024];
+                // State updates carry every seat and the room rules and can outgrow 1024 bytes
+                let mut buf = [0 as u8; 4096];
 
                 let (size, _) = udp_socket_for_listener
                     .recv_from(&mut buf)
@@ -128,12 +152,99 @@ impl OnlineGameLogic {
 
         result
     }
+
+    // Applies whatever the server sent since the last call, a message that cannot be read is
+    // skipped and reported once the rest are applied
+    pub fn process_messages(&mut self) -> Result<(), String> {
+        let messages: Vec<GameMessage> = self
+            .message_queue
+            .lock()
+            .map_err(|e| format!("Failed to lock message queue: {}", e))?
+            .drain(..)
+            .collect();
+
+        let mut errors = Vec::new();
+        for message in messages {
+            match message.message_type {
+                MessageType::PlayerJoined => {
+                    self.player_id = Some(message.player_id);
+                    self.is_host = message.player_id == 0;
+                }
+                MessageType::UpdateState => {
+                    match PlayerView::from_message_params(&message.message_params) {
+                        Ok(view) => self.apply_view(view),
+                        Err(e) => errors.push(format!("Failed to read game state: {}", e)),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    fn apply_view(&mut self, view: PlayerView) {
+        let own_hand = view.own_hand().cloned();
+        self.own_hand_hidden = own_hand.is_none();
+        self.player_cards = own_hand.unwrap_or_default();
+
+        self.visible_hands = view
+            .seats
+            .iter()
+            .enumerate()
+            .map(|(player_id, seat)| match &seat.hand {
+                Some(hand) if player_id != view.player_id => hand.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+
+        self.player_infos.resize_with(view.seats.len(), || OnlinePlayerInfo {
+            player_id: 0,
+            player_name: String::new(),
+            player_card_count: 0,
+            player_hand_size: 0,
+            player_lives: 0,
+            player_wins: 0,
+            player_guess: 0,
+            player_guessed: false,
+        });
+        for (player_id, (player_info, seat)) in
+            self.player_infos.iter_mut().zip(&view.seats).enumerate()
+        {
+            player_info.player_id = player_id;
+            player_info.player_card_count = seat.dealt_count;
+            player_info.player_hand_size = seat.hand_size;
+            player_info.player_lives = seat.lives;
+            player_info.player_wins = seat.wins;
+            player_info.player_guess = seat.guess;
+            player_info.player_guessed = seat.guessed;
+        }
+
+        self.player_id = Some(view.player_id);
+        self.played_cards = view.played_cards;
+        self.vira = view.vira;
+        self.rules = view.rules;
+        self.player_turn = view.player_turn;
+        self.round_number = view.round_number;
+        self.forbidden_guess = view.forbidden_guess;
+        self.guessing_round = view.phase == GamePhase::Guessing;
+        self.game_over = view.phase == GamePhase::GameOver;
+        self.outcome = view.outcome;
+    }
 }
 
 impl GameLogic for OnlineGameLogic {
-    fn start_match(&mut self, inital_card_count: usize) -> super::common::CardPlayedResult {
+    // The server checks the settings, since it holds the deck
+    fn start_match(
+        &mut self,
+        inital_card_count: usize,
+    ) -> Result<CardPlayedResult, SettingsError> {
         if !self.is_host {
-            return CardPlayedResult::NextPlayer;
+            return Ok(CardPlayedResult::NextPlayer);
         }
 
         let game_message = GameMessage {
@@ -151,7 +262,7 @@ impl GameLogic for OnlineGameLogic {
             .udp_socket
             .send_to(message.as_bytes(), self.server_address);
 
-        CardPlayedResult::WaitUpdate
+        Ok(CardPlayedResult::WaitUpdate)
     }
 
     fn set_guess(&mut self, player_id: usize, guess: usize) -> Result<(), String> {
@@ -209,11 +320,12 @@ impl GameLogic for OnlineGameLogic {
     }
 
     fn get_player_cards(&self, player_id: usize) -> &Vec<Card> {
-        if player_id == self.player_id.unwrap() {
+        if Some(player_id) == self.player_id {
             return &self.player_cards;
         }
 
-        panic!("Should not access other players cards");
+        // Hands the server did not reveal stay empty
+        self.visible_hands.get(player_id).unwrap_or(&HIDDEN_HAND)
     }
 
     fn get_player_card_count(&self, player_id: usize) -> usize {
@@ -222,6 +334,12 @@ impl GameLogic for OnlineGameLogic {
         player_info.player_card_count
     }
 
+    fn get_hand_size(&self, player_id: usize) -> usize {
+        let player_info = &self.player_infos[player_id];
+
+        player_info.player_hand_size
+    }
+
     fn get_player_turn(&self) -> usize {
         self.player_turn
     }
@@ -232,14 +350,18 @@ impl GameLogic for OnlineGameLogic {
         player_info.player_guess
     }
 
+    fn has_guessed(&self, player_id: usize) -> bool {
+        self.player_infos[player_id].player_guessed
+    }
+
     fn get_player_wins(&self, player_id: usize) -> usize {
         let player_info = &self.player_infos[player_id];
 
         player_info.player_wins
     }
 
-    fn get_winner(&self) -> usize {
-        todo!();
+    fn get_outcome(&self) -> Option<GameOutcome> {
+        self.outcome.clone()
     }
 
     fn get_game_over(&self) -> bool {
@@ -257,4 +379,59 @@ impl GameLogic for OnlineGameLogic {
     fn get_player_count(&self) -> usize {
         self.player_infos.len()
     }
+
+    fn get_forbidden_guess(&self) -> Option<usize> {
+        self.forbidden_guess
+    }
+
+    fn get_rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    fn get_vira(&self) -> Option<Card> {
+        self.vira
+    }
+
+    fn is_hand_visible_to(&self, owner_id: usize, viewer_id: usize) -> bool {
+        // Only the hands the server sent to this player are known
+        if Some(viewer_id) != self.player_id {
+            return false;
+        }
+
+        if owner_id == viewer_id {
+            !self.own_hand_hidden
+        } else {
+            self.visible_hands
+                .get(owner_id)
+                .is_some_and(|hand| !hand.is_empty())
+        }
+    }
+
+    fn get_player_lives(&self, player_id: usize) -> usize {
+        let player_info = &self.player_infos[player_id];
+
+        player_info.player_lives
+    }
+
+    fn get_round_number(&self) -> usize {
+        self.round_number
+    }
+
+    fn get_total_rounds(&self) -> Option<usize> {
+        self.rules.schedule.total_rounds()
+    }
+
+    // The server does not send state diffs yet
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        Vec::new()
+    }
+
+    // Nor the history of the game
+    fn get_trick_history(&self) -> &Vec<TrickRecord> {
+        &NO_TRICKS
+    }
+
+    fn get_match_history(&self) -> &Vec<MatchRecord> {
+        &NO_MATCHES
+    }
 }
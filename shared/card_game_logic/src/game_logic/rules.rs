@@ -0,0 +1,199 @@
+use std::cmp::Ordering;
+
+use super::common::{Card, DeckSpec, PlayedCard, Rank, Suit};
+
+// Ascending strength of the manilhas: ouros, espadas, copas, paus
+const MANILHA_SUIT_ORDER: [Suit; 4] = [Suit::Diamonds, Suit::Spades, Suit::Hearts, Suit::Clubs];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrumpRule {
+    None,
+    Vira,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TieRule {
+    SuitBreaks,
+    // Equal ranks cancel each other ("cangou") and the next-highest card wins
+    Cancel(CancelledTrick),
+}
+
+// What happens to a trick when every card in it was cancelled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CancelledTrick {
+    NoWinner,
+    CarryOver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoundSchedule {
+    // Each player is dealt as many cards as they have lives left
+    ShrinkingHands,
+    // Everyone is dealt the same hand size, climbing from one card to the maximum and back down
+    AscendingDescending { max_hand_size: usize },
+}
+
+impl RoundSchedule {
+    pub fn total_rounds(&self) -> Option<usize> {
+        match self {
+            RoundSchedule::ShrinkingHands => None,
+            RoundSchedule::AscendingDescending { max_hand_size } => {
+                Some((max_hand_size * 2).saturating_sub(1))
+            }
+        }
+    }
+
+    // Rounds are numbered from one, None means the hand size follows the lives
+    pub fn hand_size(&self, round_number: usize) -> Option<usize> {
+        match self {
+            RoundSchedule::ShrinkingHands => None,
+            RoundSchedule::AscendingDescending { max_hand_size } => {
+                if round_number <= *max_hand_size {
+                    Some(round_number)
+                } else {
+                    Some((max_hand_size * 2).saturating_sub(round_number))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LifeLoss {
+    OnePerMiss,
+    // A miss costs as many lives as the guess was off by
+    Difference,
+}
+
+impl LifeLoss {
+    pub fn lives_lost(&self, guess: usize, wins: usize) -> usize {
+        match self {
+            LifeLoss::OnePerMiss => (guess != wins) as usize,
+            LifeLoss::Difference => guess.abs_diff(wins),
+        }
+    }
+}
+
+// How far back local players can take back their guesses and card plays
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UndoRule {
+    Disabled,
+    CurrentTrick,
+    CurrentMatch,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleSet {
+    pub trump: TrumpRule,
+    pub tie: TieRule,
+    // When everyone holds a single card, players guess seeing every card but their own
+    pub blind_one_card_round: bool,
+    pub schedule: RoundSchedule,
+    pub life_loss: LifeLoss,
+    // None starts every player with as many lives as initial cards
+    pub starting_lives: Option<usize>,
+    pub deck: DeckSpec,
+    // Large tables can shuffle several decks together
+    pub deck_count: usize,
+    pub undo: UndoRule,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            trump: TrumpRule::None,
+            tie: TieRule::SuitBreaks,
+            blind_one_card_round: false,
+            schedule: RoundSchedule::ShrinkingHands,
+            life_loss: LifeLoss::OnePerMiss,
+            starting_lives: None,
+            deck: DeckSpec::default(),
+            deck_count: 1,
+            undo: UndoRule::CurrentTrick,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn build_deck(&self) -> Vec<Card> {
+        (0..self.deck_count)
+            .flat_map(|_| self.deck.cards())
+            .collect()
+    }
+
+    pub fn deck_size(&self) -> usize {
+        self.deck.cards().len() * self.deck_count
+    }
+
+    pub fn manilha_rank(&self, vira: Option<Card>) -> Option<Rank> {
+        match (self.trump, vira) {
+            (TrumpRule::Vira, Some(vira)) => Some(self.deck.next_rank(vira.1)),
+            _ => None,
+        }
+    }
+
+    pub fn compare_cards(&self, a: &Card, b: &Card, vira: Option<Card>) -> Ordering {
+        match self.manilha_rank(vira) {
+            Some(manilha) if a.1 == manilha && b.1 == manilha => {
+                manilha_suit_strength(a.0).cmp(&manilha_suit_strength(b.0))
+            }
+            Some(manilha) if a.1 == manilha => Ordering::Greater,
+            Some(manilha) if b.1 == manilha => Ordering::Less,
+            _ => self.deck.compare_cards(a, b),
+        }
+    }
+
+    pub fn cards_cancel(&self, a: &Card, b: &Card, vira: Option<Card>) -> bool {
+        match self.tie {
+            TieRule::SuitBreaks => false,
+            // Manilhas never tie, their suits always rank them
+            TieRule::Cancel(_) => a.1 == b.1 && self.manilha_rank(vira) != Some(a.1),
+        }
+    }
+
+    // Keeps the trick sorted by strength with the cancelled cards at the bottom,
+    // so the last card is winning the trick unless it is cancelled too
+    pub fn insert_played_card(
+        &self,
+        played_cards: &mut Vec<PlayedCard>,
+        card: PlayedCard,
+        vira: Option<Card>,
+    ) {
+        // Identical cards from different decks go below the earlier copy, so the first one played wins
+        played_cards.insert(0, card);
+
+        let cancelled: Vec<bool> = played_cards
+            .iter()
+            .enumerate()
+            .map(|(index, played)| {
+                played_cards.iter().enumerate().any(|(other_index, other)| {
+                    index != other_index && self.cards_cancel(&played.card, &other.card, vira)
+                })
+            })
+            .collect();
+
+        for (played, cancelled) in played_cards.iter_mut().zip(cancelled) {
+            played.cancelled = cancelled;
+        }
+
+        played_cards.sort_by(|a, b| {
+            b.cancelled
+                .cmp(&a.cancelled)
+                .then_with(|| self.compare_cards(&a.card, &b.card, vira))
+        });
+    }
+
+    pub fn trick_winner(&self, played_cards: &[PlayedCard]) -> Option<usize> {
+        played_cards
+            .last()
+            .filter(|played| !played.cancelled)
+            .map(|played| played.player_id)
+    }
+}
+
+fn manilha_suit_strength(suit: Suit) -> usize {
+    MANILHA_SUIT_ORDER
+        .iter()
+        .position(|s| *s == suit)
+        .unwrap_or(0)
+}
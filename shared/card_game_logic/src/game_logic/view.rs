@@ -0,0 +1,366 @@
+use crate::game_message::MessageParam;
+
+use super::{
+    common::{Card, DeckSpec, GameOutcome, PlayedCard},
+    rules::{CancelledTrick, LifeLoss, RoundSchedule, RuleSet, TieRule, TrumpRule, UndoRule},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamePhase {
+    Guessing,
+    Playing,
+    GameOver,
+}
+
+// What one player knows about another seat
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatView {
+    pub hand_size: usize,
+    pub dealt_count: usize,
+    pub guess: usize,
+    // False while the seat has not guessed yet in this match
+    pub guessed: bool,
+    pub wins: usize,
+    pub lives: usize,
+    // Only filled in when the rules let the viewer see this hand
+    pub hand: Option<Vec<Card>>,
+}
+
+// Everything one seat is allowed to know about the game, so hidden information never leaves the logic
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerView {
+    pub player_id: usize,
+    pub phase: GamePhase,
+    pub player_turn: usize,
+    pub round_number: usize,
+    pub vira: Option<Card>,
+    // The room's rules, so online clients rank cards and count rounds like the server
+    pub rules: RuleSet,
+    pub forbidden_guess: Option<usize>,
+    pub played_cards: Vec<PlayedCard>,
+    pub seats: Vec<SeatView>,
+    pub outcome: Option<GameOutcome>,
+}
+
+impl PlayerView {
+    pub fn own_seat(&self) -> &SeatView {
+        &self.seats[self.player_id]
+    }
+
+    // None in the blind round, while the own card is still hidden
+    pub fn own_hand(&self) -> Option<&Vec<Card>> {
+        self.own_seat().hand.as_ref()
+    }
+
+    pub fn is_own_turn(&self) -> bool {
+        self.phase != GamePhase::GameOver && self.player_turn == self.player_id
+    }
+
+    // The wire format keeps one value per key, with cards written as "Suit:Rank"
+    pub fn to_message_params(&self) -> Vec<MessageParam> {
+        let mut params = vec![
+            param("player_id", self.player_id.to_string()),
+            param("phase", format!("{:?}", self.phase)),
+            param("player_turn", self.player_turn.to_string()),
+            param("round_number", self.round_number.to_string()),
+            param("player_count", self.seats.len().to_string()),
+            param(
+                "played_cards",
+                self.played_cards
+                    .iter()
+                    .map(|played_card| {
+                        format!(
+                            "{}:{}:{}",
+                            played_card.player_id,
+                            encode_card(&played_card.card),
+                            played_card.cancelled
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ];
+
+        if let Some(vira) = self.vira {
+            params.push(param("vira", encode_card(&vira)));
+        }
+
+        params.extend(encode_rules(&self.rules));
+
+        if let Some(forbidden_guess) = self.forbidden_guess {
+            params.push(param("forbidden_guess", forbidden_guess.to_string()));
+        }
+
+        for (player_id, seat) in self.seats.iter().enumerate() {
+            params.push(param(
+                &format!("seat_{}", player_id),
+                format!(
+                    "{}:{}:{}:{}:{}:{}",
+                    seat.hand_size,
+                    seat.dealt_count,
+                    seat.guess,
+                    seat.guessed as usize,
+                    seat.wins,
+                    seat.lives
+                ),
+            ));
+
+            if let Some(hand) = &seat.hand {
+                params.push(param(&format!("hand_{}", player_id), encode_cards(hand)));
+            }
+        }
+
+        if let Some(outcome) = &self.outcome {
+            params.push(param("outcome", encode_outcome(outcome)));
+        }
+
+        params
+    }
+
+    pub fn from_message_params(params: &[MessageParam]) -> Result<Self, String> {
+        let player_count: usize = parse_number(find(params, "player_count")?)?;
+
+        let seats = (0..player_count)
+            .map(|player_id| {
+                let values = split_numbers(find(params, &format!("seat_{}", player_id))?)?;
+                let [hand_size, dealt_count, guess, guessed, wins, lives] = values[..] else {
+                    return Err(format!("Invalid seat {}", player_id));
+                };
+
+                let hand = match find(params, &format!("hand_{}", player_id)) {
+                    Ok(hand) => Some(decode_cards(hand)?),
+                    Err(_) => None,
+                };
+
+                Ok(SeatView {
+                    hand_size,
+                    dealt_count,
+                    guess,
+                    guessed: guessed == 1,
+                    wins,
+                    lives,
+                    hand,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let played_cards = split_list(find(params, "played_cards")?)
+            .map(|value| {
+                let parts: Vec<&str> = value.splitn(2, ':').collect();
+                let [player_id, rest] = parts[..] else {
+                    return Err(format!("Invalid played card {}", value));
+                };
+                let (card, cancelled) = rest
+                    .rsplit_once(':')
+                    .ok_or_else(|| format!("Invalid played card {}", value))?;
+
+                Ok(PlayedCard {
+                    player_id: parse_number(player_id)?,
+                    card: decode_card(card)?,
+                    cancelled: cancelled == "true",
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let phase = match find(params, "phase")? {
+            "Guessing" => GamePhase::Guessing,
+            "Playing" => GamePhase::Playing,
+            "GameOver" => GamePhase::GameOver,
+            phase => return Err(format!("Invalid phase {}", phase)),
+        };
+
+        Ok(PlayerView {
+            player_id: parse_number(find(params, "player_id")?)?,
+            phase,
+            player_turn: parse_number(find(params, "player_turn")?)?,
+            round_number: parse_number(find(params, "round_number")?)?,
+            vira: find(params, "vira").ok().map(decode_card).transpose()?,
+            rules: decode_rules(params)?,
+            forbidden_guess: find(params, "forbidden_guess")
+                .ok()
+                .map(parse_number)
+                .transpose()?,
+            played_cards,
+            seats,
+            outcome: find(params, "outcome")
+                .ok()
+                .map(decode_outcome)
+                .transpose()?,
+        })
+    }
+}
+
+fn param(key: &str, value: String) -> MessageParam {
+    MessageParam {
+        key: key.to_string(),
+        value,
+    }
+}
+
+fn find<'a>(params: &'a [MessageParam], key: &str) -> Result<&'a str, String> {
+    params
+        .iter()
+        .find(|param| param.key == key)
+        .map(|param| param.value.as_str())
+        .ok_or_else(|| format!("Missing parameter {}", key))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("Invalid number {}", value))
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').filter(|item| !item.is_empty())
+}
+
+fn split_numbers(value: &str) -> Result<Vec<usize>, String> {
+    value.split(':').map(parse_number).collect()
+}
+
+fn encode_card(card: &Card) -> String {
+    format!("{:?}:{:?}", card.0, card.1)
+}
+
+fn decode_card(value: &str) -> Result<Card, String> {
+    let invalid_card = || format!("Invalid card {}", value);
+    let (suit, rank) = value.split_once(':').ok_or_else(invalid_card)?;
+
+    Ok(Card(
+        suit.parse().map_err(|_| invalid_card())?,
+        rank.parse().map_err(|_| invalid_card())?,
+    ))
+}
+
+fn encode_cards(cards: &[Card]) -> String {
+    cards.iter().map(encode_card).collect::<Vec<_>>().join(",")
+}
+
+fn decode_cards(value: &str) -> Result<Vec<Card>, String> {
+    split_list(value).map(decode_card).collect()
+}
+
+fn encode_rules(rules: &RuleSet) -> Vec<MessageParam> {
+    let tie = match rules.tie {
+        TieRule::SuitBreaks => "SuitBreaks".to_string(),
+        TieRule::Cancel(cancelled_trick) => format!("Cancel:{:?}", cancelled_trick),
+    };
+
+    let schedule = match rules.schedule {
+        RoundSchedule::ShrinkingHands => "ShrinkingHands".to_string(),
+        RoundSchedule::AscendingDescending { max_hand_size } => {
+            format!("AscendingDescending:{}", max_hand_size)
+        }
+    };
+
+    let mut params = vec![
+        param("trump", format!("{:?}", rules.trump)),
+        param("tie", tie),
+        param("blind_round", rules.blind_one_card_round.to_string()),
+        param("schedule", schedule),
+        param("life_loss", format!("{:?}", rules.life_loss)),
+        param(
+            "deck",
+            rules
+                .deck
+                .rank_order
+                .iter()
+                .map(|rank| format!("{:?}", rank))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        param("deck_count", rules.deck_count.to_string()),
+        param("undo", format!("{:?}", rules.undo)),
+    ];
+
+    if let Some(starting_lives) = rules.starting_lives {
+        params.push(param("starting_lives", starting_lives.to_string()));
+    }
+
+    params
+}
+
+fn decode_rules(params: &[MessageParam]) -> Result<RuleSet, String> {
+    let trump = match find(params, "trump")? {
+        "None" => TrumpRule::None,
+        "Vira" => TrumpRule::Vira,
+        trump => return Err(format!("Invalid trump rule {}", trump)),
+    };
+
+    let tie = match find(params, "tie")? {
+        "SuitBreaks" => TieRule::SuitBreaks,
+        "Cancel:NoWinner" => TieRule::Cancel(CancelledTrick::NoWinner),
+        "Cancel:CarryOver" => TieRule::Cancel(CancelledTrick::CarryOver),
+        tie => return Err(format!("Invalid tie rule {}", tie)),
+    };
+
+    let schedule = find(params, "schedule")?;
+    let schedule = match schedule.split_once(':') {
+        None if schedule == "ShrinkingHands" => RoundSchedule::ShrinkingHands,
+        Some(("AscendingDescending", max_hand_size)) => RoundSchedule::AscendingDescending {
+            max_hand_size: parse_number(max_hand_size)?,
+        },
+        _ => return Err(format!("Invalid schedule {}", schedule)),
+    };
+
+    let life_loss = match find(params, "life_loss")? {
+        "OnePerMiss" => LifeLoss::OnePerMiss,
+        "Difference" => LifeLoss::Difference,
+        life_loss => return Err(format!("Invalid life loss {}", life_loss)),
+    };
+
+    let undo = match find(params, "undo")? {
+        "Disabled" => UndoRule::Disabled,
+        "CurrentTrick" => UndoRule::CurrentTrick,
+        "CurrentMatch" => UndoRule::CurrentMatch,
+        undo => return Err(format!("Invalid undo rule {}", undo)),
+    };
+
+    let rank_order = split_list(find(params, "deck")?)
+        .map(|rank| rank.parse().map_err(|_| format!("Invalid rank {}", rank)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(RuleSet {
+        trump,
+        tie,
+        blind_one_card_round: find(params, "blind_round")? == "true",
+        schedule,
+        life_loss,
+        starting_lives: find(params, "starting_lives")
+            .ok()
+            .map(parse_number)
+            .transpose()?,
+        deck: DeckSpec::custom(rank_order),
+        deck_count: parse_number(find(params, "deck_count")?)?,
+        undo,
+    })
+}
+
+fn encode_outcome(outcome: &GameOutcome) -> String {
+    match outcome {
+        GameOutcome::Winner(player_id) => format!("winner:{}", player_id),
+        GameOutcome::Draw(player_ids) => format!(
+            "draw:{}",
+            player_ids
+                .iter()
+                .map(|player_id| player_id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        GameOutcome::Abandoned => "abandoned".to_string(),
+    }
+}
+
+fn decode_outcome(value: &str) -> Result<GameOutcome, String> {
+    match value.split_once(':') {
+        Some(("winner", player_id)) => Ok(GameOutcome::Winner(parse_number(player_id)?)),
+        Some(("draw", player_ids)) => Ok(GameOutcome::Draw(
+            split_list(player_ids)
+                .map(parse_number)
+                .collect::<Result<_, _>>()?,
+        )),
+        None if value == "abandoned" => Ok(GameOutcome::Abandoned),
+        _ => Err(format!("Invalid outcome {}", value)),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bot;
 pub mod game_logic;
 pub mod game_message;
 pub mod server_options;
\ No newline at end of file
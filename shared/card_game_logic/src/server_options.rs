@@ -14,3 +14,14 @@ pub fn get_server_bind_addr() -> SocketAddr {
     let port = SERVER_PORT.lock().unwrap();
     SocketAddr::from(([0, 0, 0, 0], *port))
 }
+
+// The server only listens on IPv4, so other addresses are refused
+pub fn set_server_addr(addr: SocketAddr) -> Result<(), String> {
+    let SocketAddr::V4(addr) = addr else {
+        return Err(format!("{} is not an IPv4 address", addr));
+    };
+
+    *SERVER_IP.lock().unwrap() = addr.ip().octets().map(i32::from);
+    *SERVER_PORT.lock().unwrap() = addr.port();
+    Ok(())
+}
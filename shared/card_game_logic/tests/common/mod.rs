@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+use card_game_logic::game_logic::{
+    GameLogic,
+    common::{Card, CardPlayedResult},
+    local::LocalGameLogic,
+    rules::RuleSet,
+};
+use rand::{Rng, rngs::StdRng};
+
+// Seeded before the first deal, so the same seed always deals the same hands
+pub fn new_game(
+    player_count: usize,
+    rules: RuleSet,
+    initial_card_count: usize,
+    seed: u64,
+) -> LocalGameLogic {
+    let mut game_logic = LocalGameLogic::default();
+    game_logic.set_seed(seed);
+    game_logic.init(player_count, rules);
+    game_logic.start_match(initial_card_count).unwrap();
+
+    game_logic
+}
+
+// Replaces the shuffled hands of the current match with known ones
+pub fn deal(game_logic: &mut LocalGameLogic, hands: Vec<Vec<Card>>) {
+    game_logic.player_card_count = hands.iter().map(|hand| hand.len()).collect();
+    game_logic.player_cards = hands;
+}
+
+pub fn guess(game_logic: &mut LocalGameLogic, guesses: &[(usize, usize)]) {
+    for (player_id, guess) in guesses {
+        assert_eq!(game_logic.get_player_turn(), *player_id);
+        game_logic.set_guess(*player_id, *guess).unwrap();
+    }
+}
+
+pub fn play(game_logic: &mut LocalGameLogic, plays: &[(usize, Card)]) -> CardPlayedResult {
+    let mut result = CardPlayedResult::NextPlayer;
+    for (player_id, card) in plays {
+        assert_eq!(game_logic.get_player_turn(), *player_id);
+        result = game_logic.play_card(*player_id, card).unwrap();
+    }
+
+    result
+}
+
+pub fn allowed_guesses(game_logic: &LocalGameLogic) -> Vec<usize> {
+    let player_id = game_logic.get_player_turn();
+    (0..=game_logic.get_player_cards(player_id).len())
+        .filter(|guess| game_logic.get_forbidden_guess() != Some(*guess))
+        .collect()
+}
+
+// Makes one random legal guess or card play for whoever's turn it is
+pub fn random_step(game_logic: &mut LocalGameLogic, rng: &mut StdRng) -> Result<(), String> {
+    let player_id = game_logic.get_player_turn();
+
+    if game_logic.get_guessing_round() {
+        let guesses = allowed_guesses(game_logic);
+        let guess = guesses[rng.random_range(0..guesses.len())];
+        game_logic.set_guess(player_id, guess)
+    } else {
+        let hand = game_logic.get_player_cards(player_id);
+        let card = hand[rng.random_range(0..hand.len())];
+        game_logic.play_card(player_id, &card).map(|_| ())
+    }
+}
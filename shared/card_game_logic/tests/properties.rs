@@ -0,0 +1,148 @@
+mod common;
+
+use card_game_logic::game_logic::{
+    GameLogic, GameSettings,
+    common::{Card, DeckSpec},
+    events::GameEvent,
+    local::LocalGameLogic,
+    rules::{CancelledTrick, LifeLoss, RoundSchedule, RuleSet, TieRule, TrumpRule},
+};
+use proptest::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+use common::{new_game, random_step};
+
+const MAX_STEPS: usize = 10_000;
+
+fn rules_strategy() -> impl Strategy<Value = RuleSet> {
+    (
+        prop_oneof![Just(TrumpRule::None), Just(TrumpRule::Vira)],
+        prop_oneof![
+            Just(TieRule::SuitBreaks),
+            Just(TieRule::Cancel(CancelledTrick::NoWinner)),
+            Just(TieRule::Cancel(CancelledTrick::CarryOver)),
+        ],
+        prop_oneof![
+            Just(RoundSchedule::ShrinkingHands),
+            (1..=4usize)
+                .prop_map(|max_hand_size| RoundSchedule::AscendingDescending { max_hand_size }),
+        ],
+        prop_oneof![Just(LifeLoss::OnePerMiss), Just(LifeLoss::Difference)],
+        prop_oneof![Just(DeckSpec::classic()), Just(DeckSpec::truco())],
+        proptest::option::of(1..=4usize),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(trump, tie, schedule, life_loss, deck, starting_lives, blind_one_card_round)| {
+                RuleSet {
+                    trump,
+                    tie,
+                    schedule,
+                    life_loss,
+                    deck,
+                    starting_lives,
+                    blind_one_card_round,
+                    ..RuleSet::default()
+                }
+            },
+        )
+}
+
+fn sorted(mut cards: Vec<Card>) -> Vec<Card> {
+    cards.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    cards
+}
+
+// Every card of the deck is in a hand, on the table, in the stock, under the vira or already played
+fn check_card_conservation(game_logic: &LocalGameLogic, discarded: &[Card]) {
+    let mut cards: Vec<Card> = (0..game_logic.get_player_count())
+        .flat_map(|p| game_logic.get_player_cards(p).clone())
+        .collect();
+    cards.extend(game_logic.get_played_cards().iter().map(|p| p.card));
+    cards.extend(game_logic.deck.iter().copied());
+    cards.extend(game_logic.get_vira());
+    cards.extend(discarded.iter().copied());
+
+    assert_eq!(sorted(cards), sorted(game_logic.rules.build_deck()));
+}
+
+fn check_turn(game_logic: &LocalGameLogic) {
+    let player_id = game_logic.get_player_turn();
+    assert!(game_logic.get_player_lives(player_id) > 0);
+    assert!(game_logic.get_player_card_count(player_id) > 0);
+
+    if !game_logic.get_guessing_round() {
+        assert!(!game_logic.get_player_cards(player_id).is_empty());
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_legal_games_keep_the_invariants(
+        seed in any::<u64>(),
+        player_count in 2..=6usize,
+        initial_card_count in 1..=5usize,
+        rules in rules_strategy(),
+    ) {
+        let settings = GameSettings {
+            player_count,
+            initial_card_count,
+            rules: rules.clone(),
+        };
+        prop_assume!(settings.validate().is_ok());
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut game_logic = new_game(player_count, rules.clone(), initial_card_count, seed);
+
+        let mut discarded = Vec::new();
+        let mut cards_left = vec![0; player_count];
+        let mut trick_cards = 0;
+        let mut trick_players = 0;
+        let mut steps = 0;
+
+        while !game_logic.get_game_over() {
+            prop_assert!(steps < MAX_STEPS, "game did not terminate");
+            steps += 1;
+
+            check_turn(&game_logic);
+            random_step(&mut game_logic, &mut rng).unwrap();
+
+            for event in game_logic.take_events() {
+                match event {
+                    GameEvent::MatchStarted { hand_sizes, .. } => {
+                        discarded.clear();
+                        cards_left = hand_sizes;
+                    }
+                    GameEvent::CardPlayed { player_id, card } => {
+                        if trick_cards == 0 {
+                            trick_players = cards_left.iter().filter(|left| **left > 0).count();
+                        }
+
+                        discarded.push(card);
+                        cards_left[player_id] -= 1;
+                        trick_cards += 1;
+                    }
+                    GameEvent::TrickFinished { winner, .. } => {
+                        // Each trick ends once, after everyone still holding cards played
+                        prop_assert_eq!(trick_cards, trick_players);
+                        trick_cards = 0;
+
+                        if rules.tie == TieRule::SuitBreaks {
+                            prop_assert!(winner.is_some());
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            if !game_logic.get_game_over() {
+                // Cards on the table were already counted when played
+                let on_table = game_logic.get_played_cards().len();
+                check_card_conservation(&game_logic, &discarded[..discarded.len() - on_table]);
+            }
+        }
+
+        prop_assert!(game_logic.get_outcome().is_some());
+        prop_assert!(game_logic.set_guess(game_logic.get_player_turn(), 0).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+mod common;
+
+use card_game_logic::game_logic::{
+    GameLogic, SettingsError,
+    common::{Card, CardPlayedResult, DeckSpec, GameOutcome, Rank, Suit},
+    events::GameEvent,
+    local::LocalGameLogic,
+    rules::{CancelledTrick, LifeLoss, RoundSchedule, RuleSet, TieRule, TrumpRule, UndoRule},
+    view::PlayerView,
+};
+
+use common::{deal, guess, new_game, play};
+
+fn card(suit: Suit, rank: Rank) -> Card {
+    Card(suit, rank)
+}
+
+#[test]
+fn forbidden_guess_only_applies_to_last_player() {
+    let mut game_logic = new_game(3, RuleSet::default(), 2, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Spades, Rank::King), card(Suit::Clubs, Rank::Two)],
+            vec![
+                card(Suit::Hearts, Rank::Queen),
+                card(Suit::Clubs, Rank::Three),
+            ],
+            vec![
+                card(Suit::Diamonds, Rank::Jack),
+                card(Suit::Clubs, Rank::Four),
+            ],
+        ],
+    );
+
+    assert_eq!(game_logic.get_forbidden_guess(), None);
+    guess(&mut game_logic, &[(0, 1)]);
+    assert_eq!(game_logic.get_forbidden_guess(), None);
+    guess(&mut game_logic, &[(1, 0)]);
+
+    // The guesses cannot add up to the number of tricks in the match
+    assert_eq!(game_logic.get_forbidden_guess(), Some(1));
+    assert!(game_logic.set_guess(2, 1).is_err());
+    assert_eq!(game_logic.get_player_turn(), 2);
+
+    guess(&mut game_logic, &[(2, 0)]);
+    assert!(!game_logic.get_guessing_round());
+    assert_eq!(game_logic.get_player_turn(), 0);
+}
+
+#[test]
+fn trick_winner_leads_the_next_trick() {
+    let mut game_logic = new_game(3, RuleSet::default(), 2, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Clubs, Rank::Two), card(Suit::Clubs, Rank::Three)],
+            vec![
+                card(Suit::Spades, Rank::King),
+                card(Suit::Clubs, Rank::Four),
+            ],
+            vec![card(Suit::Clubs, Rank::Five), card(Suit::Clubs, Rank::Six)],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 0), (1, 1), (2, 0)]);
+
+    let result = play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Clubs, Rank::Two)),
+            (1, card(Suit::Spades, Rank::King)),
+            (2, card(Suit::Clubs, Rank::Five)),
+        ],
+    );
+    assert_eq!(result, CardPlayedResult::NextTurn);
+    assert_eq!(game_logic.get_player_turn(), 1);
+    assert_eq!(game_logic.get_player_wins(1), 1);
+    assert!(game_logic.get_played_cards().is_empty());
+
+    let result = play(
+        &mut game_logic,
+        &[
+            (1, card(Suit::Clubs, Rank::Four)),
+            (2, card(Suit::Clubs, Rank::Six)),
+            (0, card(Suit::Clubs, Rank::Three)),
+        ],
+    );
+    assert_eq!(result, CardPlayedResult::NextMatch);
+
+    // Only the third player missed their guess, so only their next hand shrinks
+    assert_eq!(
+        (0..3)
+            .map(|p| game_logic.get_player_lives(p))
+            .collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(
+        (0..3)
+            .map(|p| game_logic.get_player_card_count(p))
+            .collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(game_logic.get_round_number(), 2);
+}
+
+#[test]
+fn eliminated_player_is_skipped_for_turns_and_the_last_guess() {
+    let rules = RuleSet {
+        starting_lives: Some(1),
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(3, rules, 1, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Spades, Rank::King)],
+            vec![card(Suit::Clubs, Rank::Two)],
+            vec![card(Suit::Clubs, Rank::Three)],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 1), (1, 0)]);
+    assert_eq!(game_logic.get_forbidden_guess(), Some(0));
+    guess(&mut game_logic, &[(2, 1)]);
+
+    let result = play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Spades, Rank::King)),
+            (1, card(Suit::Clubs, Rank::Two)),
+            (2, card(Suit::Clubs, Rank::Three)),
+        ],
+    );
+    assert_eq!(result, CardPlayedResult::NextMatch);
+    assert_eq!(game_logic.get_player_lives(2), 0);
+    assert_eq!(game_logic.get_player_card_count(2), 0);
+
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Diamonds, Rank::Five)],
+            vec![card(Suit::Hearts, Rank::Nine)],
+            vec![],
+        ],
+    );
+
+    // The second player guesses last now that the third seat is empty
+    guess(&mut game_logic, &[(0, 0)]);
+    assert_eq!(game_logic.get_forbidden_guess(), Some(1));
+    assert!(game_logic.set_guess(1, 1).is_err());
+    guess(&mut game_logic, &[(1, 0)]);
+    assert_eq!(game_logic.get_player_turn(), 0);
+
+    play(&mut game_logic, &[(0, card(Suit::Diamonds, Rank::Five))]);
+    assert_eq!(game_logic.get_player_turn(), 1);
+
+    let result = play(&mut game_logic, &[(1, card(Suit::Hearts, Rank::Nine))]);
+    assert_eq!(result, CardPlayedResult::GameOver);
+    assert_eq!(game_logic.get_outcome(), Some(GameOutcome::Winner(0)));
+    assert!(
+        game_logic
+            .play_card(0, &card(Suit::Diamonds, Rank::Five))
+            .is_err()
+    );
+}
+
+#[test]
+fn manilha_beats_higher_ranks() {
+    let rules = RuleSet {
+        trump: TrumpRule::Vira,
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(2, rules, 1, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Diamonds, Rank::Five)],
+            vec![card(Suit::Spades, Rank::King)],
+        ],
+    );
+    game_logic.vira = Some(card(Suit::Hearts, Rank::Four));
+    guess(&mut game_logic, &[(0, 1), (1, 1)]);
+    game_logic.take_events();
+
+    play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Diamonds, Rank::Five)),
+            (1, card(Suit::Spades, Rank::King)),
+        ],
+    );
+
+    assert!(
+        game_logic
+            .take_events()
+            .contains(&GameEvent::TrickFinished {
+                winner: Some(0),
+                tricks_won: 1,
+            })
+    );
+}
+
+#[test]
+fn equal_ranks_cancel_and_the_next_highest_card_wins() {
+    let rules = RuleSet {
+        tie: TieRule::Cancel(CancelledTrick::NoWinner),
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(3, rules, 1, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Spades, Rank::King)],
+            vec![card(Suit::Hearts, Rank::King)],
+            vec![card(Suit::Clubs, Rank::Two)],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 1), (1, 1), (2, 0)]);
+    game_logic.take_events();
+
+    play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Spades, Rank::King)),
+            (1, card(Suit::Hearts, Rank::King)),
+            (2, card(Suit::Clubs, Rank::Two)),
+        ],
+    );
+
+    assert!(
+        game_logic
+            .take_events()
+            .contains(&GameEvent::TrickFinished {
+                winner: Some(2),
+                tricks_won: 1,
+            })
+    );
+}
+
+#[test]
+fn cancelled_trick_carries_over_to_the_next_winner() {
+    let rules = RuleSet {
+        tie: TieRule::Cancel(CancelledTrick::CarryOver),
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(2, rules, 2, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Spades, Rank::King), card(Suit::Clubs, Rank::Two)],
+            vec![
+                card(Suit::Hearts, Rank::King),
+                card(Suit::Clubs, Rank::Three),
+            ],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 0), (1, 1)]);
+    game_logic.take_events();
+
+    play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Spades, Rank::King)),
+            (1, card(Suit::Hearts, Rank::King)),
+        ],
+    );
+
+    // Nobody wins the cancelled trick, so the player who led it leads again
+    assert_eq!(game_logic.get_player_turn(), 0);
+    assert_eq!(game_logic.get_player_wins(0), 0);
+    assert_eq!(game_logic.get_player_wins(1), 0);
+
+    play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Clubs, Rank::Two)),
+            (1, card(Suit::Clubs, Rank::Three)),
+        ],
+    );
+
+    let events = game_logic.take_events();
+    assert!(events.contains(&GameEvent::TrickFinished {
+        winner: None,
+        tricks_won: 0,
+    }));
+    assert!(events.contains(&GameEvent::TrickFinished {
+        winner: Some(1),
+        tricks_won: 2,
+    }));
+}
+
+#[test]
+fn undo_and_redo_a_card_until_the_trick_resolves() {
+    let mut game_logic = new_game(2, RuleSet::default(), 1, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Spades, Rank::King)],
+            vec![card(Suit::Clubs, Rank::Two)],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 1), (1, 1)]);
+    play(&mut game_logic, &[(0, card(Suit::Spades, Rank::King))]);
+
+    game_logic.undo().unwrap();
+    assert_eq!(game_logic.get_player_turn(), 0);
+    assert!(game_logic.get_played_cards().is_empty());
+    assert_eq!(
+        game_logic.get_player_cards(0),
+        &vec![card(Suit::Spades, Rank::King)]
+    );
+    assert!(game_logic.can_redo());
+
+    game_logic.redo().unwrap();
+    assert_eq!(game_logic.get_player_turn(), 1);
+    assert_eq!(game_logic.get_played_cards().len(), 1);
+    assert!(game_logic.get_player_cards(0).is_empty());
+
+    play(&mut game_logic, &[(1, card(Suit::Clubs, Rank::Two))]);
+    assert!(!game_logic.can_undo());
+    assert!(game_logic.undo().is_err());
+}
+
+#[test]
+fn undo_takes_back_a_guess_unless_disabled() {
+    let mut game_logic = new_game(3, RuleSet::default(), 2, 0);
+    guess(&mut game_logic, &[(0, 2)]);
+
+    game_logic.undo().unwrap();
+    assert!(game_logic.get_guessing_round());
+    assert_eq!(game_logic.get_player_turn(), 0);
+    assert_eq!(game_logic.get_player_guess(0), 0);
+
+    // A new move drops whatever could have been redone
+    guess(&mut game_logic, &[(0, 1)]);
+    assert!(!game_logic.can_redo());
+
+    let rules = RuleSet {
+        undo: UndoRule::Disabled,
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(3, rules, 2, 0);
+    guess(&mut game_logic, &[(0, 2)]);
+    assert!(!game_logic.can_undo());
+}
+
+#[test]
+fn player_view_hides_other_hands_and_survives_the_wire() {
+    let rules = RuleSet {
+        trump: TrumpRule::Vira,
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(3, rules, 2, 0);
+    guess(&mut game_logic, &[(0, 1)]);
+
+    let view = game_logic.get_player_view(1);
+    assert_eq!(view.own_hand(), Some(game_logic.get_player_cards(1)));
+    assert!(view.seats[0].hand.is_none());
+    assert!(view.seats[2].hand.is_none());
+    assert_eq!(view.seats[0].guess, 1);
+    assert_eq!(view.seats[2].hand_size, 2);
+    assert!(view.is_own_turn());
+
+    let params = view.to_message_params();
+    assert_eq!(PlayerView::from_message_params(&params), Ok(view));
+}
+
+#[test]
+fn player_view_carries_the_room_rules_over_the_wire() {
+    let rules = RuleSet {
+        trump: TrumpRule::Vira,
+        tie: TieRule::Cancel(CancelledTrick::CarryOver),
+        schedule: RoundSchedule::AscendingDescending { max_hand_size: 4 },
+        life_loss: LifeLoss::Difference,
+        starting_lives: Some(3),
+        deck: DeckSpec::truco(),
+        undo: UndoRule::Disabled,
+        ..RuleSet::default()
+    };
+    let game_logic = new_game(3, rules.clone(), 1, 0);
+
+    let view = game_logic.get_player_view(2);
+    let view = PlayerView::from_message_params(&view.to_message_params()).unwrap();
+    assert_eq!(view.rules, rules);
+}
+
+#[test]
+fn tricks_and_matches_are_recorded_in_play_order() {
+    let mut game_logic = new_game(3, RuleSet::default(), 2, 0);
+    deal(
+        &mut game_logic,
+        vec![
+            vec![card(Suit::Clubs, Rank::Two), card(Suit::Clubs, Rank::Three)],
+            vec![
+                card(Suit::Spades, Rank::King),
+                card(Suit::Clubs, Rank::Four),
+            ],
+            vec![card(Suit::Clubs, Rank::Five), card(Suit::Clubs, Rank::Six)],
+        ],
+    );
+    guess(&mut game_logic, &[(0, 0), (1, 1), (2, 0)]);
+    play(
+        &mut game_logic,
+        &[
+            (0, card(Suit::Clubs, Rank::Two)),
+            (1, card(Suit::Spades, Rank::King)),
+            (2, card(Suit::Clubs, Rank::Five)),
+        ],
+    );
+    play(
+        &mut game_logic,
+        &[
+            (1, card(Suit::Clubs, Rank::Four)),
+            (2, card(Suit::Clubs, Rank::Six)),
+            (0, card(Suit::Clubs, Rank::Three)),
+        ],
+    );
+
+    // A new match started, so its trick history is empty again
+    assert!(game_logic.get_trick_history().is_empty());
+
+    let [record] = &game_logic.get_match_history()[..] else {
+        panic!("Expected a single finished match");
+    };
+    assert_eq!(record.wins, vec![0, 1, 1]);
+    assert_eq!(record.lives_lost, vec![0, 0, 1]);
+
+    let second_trick = &record.tricks[1];
+    assert_eq!(second_trick.lead, 1);
+    assert_eq!(second_trick.winner, Some(2));
+    assert_eq!(
+        second_trick
+            .cards
+            .iter()
+            .map(|played| played.player_id)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 0]
+    );
+
+    let stats = game_logic.get_player_stats(2);
+    assert_eq!(stats.matches_played, 1);
+    assert_eq!(stats.guesses_hit, 0);
+    assert_eq!(stats.tricks_won, 1);
+    assert_eq!(stats.lives_lost_per_match(), 1.0);
+    assert_eq!(game_logic.get_player_stats(1).guess_hit_rate(), 1.0);
+}
+
+#[test]
+fn moves_before_the_match_starts_are_rejected() {
+    let mut game_logic = LocalGameLogic::default();
+    assert!(game_logic.set_guess(0, 1).is_err());
+    assert!(game_logic.play_card(0, &card(Suit::Clubs, Rank::Two)).is_err());
+
+    game_logic.init(3, RuleSet::default());
+    assert!(game_logic.set_guess(0, 1).is_err());
+    assert!(game_logic.play_card(0, &card(Suit::Clubs, Rank::Two)).is_err());
+}
+
+#[test]
+fn player_view_marks_the_seats_that_already_guessed() {
+    let mut game_logic = new_game(3, RuleSet::default(), 2, 0);
+    guess(&mut game_logic, &[(0, 0)]);
+
+    let view = game_logic.get_player_view(1);
+    let guessed: Vec<bool> = view.seats.iter().map(|seat| seat.guessed).collect();
+    assert_eq!(guessed, vec![true, false, false]);
+
+    let view = PlayerView::from_message_params(&view.to_message_params()).unwrap();
+    assert!(view.seats[0].guessed);
+}
+
+#[test]
+fn guesses_above_the_hand_size_are_rejected() {
+    let rules = RuleSet {
+        schedule: RoundSchedule::AscendingDescending { max_hand_size: 3 },
+        ..RuleSet::default()
+    };
+    let mut game_logic = new_game(3, rules, 3, 0);
+
+    // The first match of the schedule deals a single card
+    assert!(game_logic.set_guess(0, 2).is_err());
+    assert_eq!(game_logic.get_player_turn(), 0);
+    guess(&mut game_logic, &[(0, 1)]);
+    assert_eq!(game_logic.get_player_turn(), 1);
+}
+
+#[test]
+fn starting_a_table_the_deck_cannot_deal_is_an_error() {
+    let mut game_logic = LocalGameLogic::default();
+    game_logic.init(8, RuleSet::default());
+
+    assert_eq!(
+        game_logic.start_match(7),
+        Err(SettingsError::NotEnoughCards {
+            needed: 56,
+            available: 52
+        })
+    );
+    assert!(game_logic.set_guess(0, 1).is_err());
+}
+
+#[test]
+fn rank_lists_round_trip_through_the_deck_spec() {
+    let deck = DeckSpec::from_rank_list("4, 5,6,7,q,J,K,A,2,3").unwrap();
+    assert_eq!(deck, DeckSpec::truco());
+    assert_eq!(deck.rank_list(), "4,5,6,7,Q,J,K,A,2,3");
+
+    assert!(DeckSpec::from_rank_list("2,3,2").is_err());
+    assert!(DeckSpec::from_rank_list("2,3,11").is_err());
+}
@@ -0,0 +1,29 @@
+use std::{env, panic, process};
+
+use options::SimOptions;
+use report::Report;
+
+pub mod options;
+pub mod report;
+pub mod simulation;
+
+fn main() {
+    let options = match SimOptions::from_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            if !error.is_empty() {
+                eprintln!("{}", error);
+            }
+            eprintln!("{}", options::USAGE);
+            process::exit(2);
+        }
+    };
+
+    // Panics are collected per game and listed in the report instead
+    panic::set_hook(Box::new(|_| {}));
+
+    let records = simulation::run_games(&options);
+    let report = Report::new(&options, &records);
+
+    print!("{}", report.render(options.format));
+}
@@ -0,0 +1,182 @@
+use card_game_logic::{
+    bot::BotDifficulty,
+    game_logic::{
+        GameSettings,
+        common::DeckSpec,
+        rules::{CancelledTrick, LifeLoss, RoundSchedule, RuleSet, TieRule, TrumpRule},
+    },
+};
+
+pub const USAGE: &str = "Usage: guessing_sim [--games N] [--seed N] [--bots easy,medium,hard] \
+[--cards N] [--max-steps N] [--trump none|vira] [--ties suit|cancel|carry-over] [--blind-round on|off] [--schedule shrinking|up-down:N] [--lives N] [--life-loss one|difference] [--deck classic|poker|truco|custom:R,R,...] [--decks N] [--format text|csv|json]";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+pub struct SimOptions {
+    pub games: usize,
+    pub seed: u64,
+    pub bots: Vec<BotDifficulty>,
+    pub initial_card_count: usize,
+    pub max_steps: usize,
+    pub rules: RuleSet,
+    pub format: OutputFormat,
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        Self {
+            games: 1000,
+            seed: 0,
+            bots: vec![
+                BotDifficulty::Easy,
+                BotDifficulty::Medium,
+                BotDifficulty::Hard,
+            ],
+            initial_card_count: 3,
+            max_steps: 100_000,
+            rules: RuleSet::default(),
+            format: OutputFormat::Text,
+        }
+    }
+}
+
+impl SimOptions {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = SimOptions::default();
+
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(String::new());
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+
+            match arg.as_str() {
+                "--games" => options.games = parse_number(&arg, &value)?,
+                "--seed" => options.seed = parse_number(&arg, &value)?,
+                "--cards" => options.initial_card_count = parse_number(&arg, &value)?,
+                "--max-steps" => options.max_steps = parse_number(&arg, &value)?,
+                "--bots" => {
+                    options.bots = value
+                        .split(',')
+                        .map(parse_bot)
+                        .collect::<Result<Vec<_>, _>>()?;
+                }
+                "--trump" => {
+                    options.rules.trump = match value.as_str() {
+                        "none" => TrumpRule::None,
+                        "vira" => TrumpRule::Vira,
+                        _ => return Err(format!("Unknown trump rule: {}", value)),
+                    };
+                }
+                "--ties" => {
+                    options.rules.tie = match value.as_str() {
+                        "suit" => TieRule::SuitBreaks,
+                        "cancel" => TieRule::Cancel(CancelledTrick::NoWinner),
+                        "carry-over" => TieRule::Cancel(CancelledTrick::CarryOver),
+                        _ => return Err(format!("Unknown tie rule: {}", value)),
+                    };
+                }
+                "--blind-round" => {
+                    options.rules.blind_one_card_round = match value.as_str() {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(format!("Invalid value for {}: {}", arg, value)),
+                    };
+                }
+                "--schedule" => options.rules.schedule = parse_schedule(&value)?,
+                "--lives" => options.rules.starting_lives = Some(parse_number(&arg, &value)?),
+                "--life-loss" => {
+                    options.rules.life_loss = match value.as_str() {
+                        "one" => LifeLoss::OnePerMiss,
+                        "difference" => LifeLoss::Difference,
+                        _ => return Err(format!("Unknown life loss: {}", value)),
+                    };
+                }
+                "--deck" => options.rules.deck = parse_deck(&value)?,
+                "--decks" => options.rules.deck_count = parse_number(&arg, &value)?,
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "text" => OutputFormat::Text,
+                        "csv" => OutputFormat::Csv,
+                        "json" => OutputFormat::Json,
+                        _ => return Err(format!("Unknown format: {}", value)),
+                    };
+                }
+                _ => return Err(format!("Unknown option: {}", arg)),
+            }
+        }
+
+        if options.bots.len() < 2 {
+            return Err("At least two bots are needed".to_string());
+        }
+
+        GameSettings {
+            player_count: options.bots.len(),
+            initial_card_count: options.initial_card_count,
+            rules: options.rules.clone(),
+        }
+        .validate()
+        .map_err(|err| err.to_string())?;
+
+        Ok(options)
+    }
+}
+
+pub fn bot_name(difficulty: BotDifficulty) -> &'static str {
+    match difficulty {
+        BotDifficulty::Easy => "easy",
+        BotDifficulty::Medium => "medium",
+        BotDifficulty::Hard => "hard",
+    }
+}
+
+fn parse_bot(name: &str) -> Result<BotDifficulty, String> {
+    match name.trim() {
+        "easy" | "random" => Ok(BotDifficulty::Easy),
+        "medium" | "heuristic" => Ok(BotDifficulty::Medium),
+        "hard" | "monte-carlo" => Ok(BotDifficulty::Hard),
+        _ => Err(format!("Unknown bot: {}", name)),
+    }
+}
+
+fn parse_schedule(value: &str) -> Result<RoundSchedule, String> {
+    if value == "shrinking" {
+        return Ok(RoundSchedule::ShrinkingHands);
+    }
+
+    match value.strip_prefix("up-down:") {
+        Some(max_hand_size) => Ok(RoundSchedule::AscendingDescending {
+            max_hand_size: parse_number("--schedule", max_hand_size)?,
+        }),
+        None => Err(format!("Unknown schedule: {}", value)),
+    }
+}
+
+fn parse_deck(value: &str) -> Result<DeckSpec, String> {
+    match value {
+        "classic" => return Ok(DeckSpec::classic()),
+        "poker" => return Ok(DeckSpec::poker()),
+        "truco" => return Ok(DeckSpec::truco()),
+        _ => (),
+    }
+
+    // Custom ranks are listed from weakest to strongest, e.g. custom:4,5,6,7,Q,J,K,A,2,3
+    match value.strip_prefix("custom:") {
+        Some(ranks) => DeckSpec::from_rank_list(ranks),
+        None => Err(format!("Unknown deck: {}", value)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("Invalid value for {}: {}", arg, value))
+}
@@ -0,0 +1,330 @@
+use crate::{
+    options::{OutputFormat, SimOptions, bot_name},
+    simulation::{GameRecord, GameResult},
+};
+
+const Z_95: f64 = 1.96;
+const MAX_LISTED_FAILURES: usize = 10;
+
+pub struct EntryReport {
+    pub name: &'static str,
+    pub games: usize,
+    pub wins: usize,
+    pub guesses: usize,
+    pub guess_hits: usize,
+}
+
+pub struct FailureReport {
+    pub seed: u64,
+    pub kind: &'static str,
+    pub message: String,
+}
+
+pub struct Report {
+    pub games: usize,
+    pub finished: usize,
+    pub draws: usize,
+    pub panicked: usize,
+    pub failed: usize,
+    pub stalled: usize,
+    pub game_matches: Vec<usize>,
+    pub game_tricks: Vec<usize>,
+    pub entries: Vec<EntryReport>,
+    pub failures: Vec<FailureReport>,
+}
+
+impl Report {
+    pub fn new(options: &SimOptions, records: &[GameRecord]) -> Self {
+        let mut report = Report {
+            games: records.len(),
+            finished: 0,
+            draws: 0,
+            panicked: 0,
+            failed: 0,
+            stalled: 0,
+            game_matches: Vec::new(),
+            game_tricks: Vec::new(),
+            entries: options
+                .bots
+                .iter()
+                .map(|bot| EntryReport {
+                    name: bot_name(*bot),
+                    games: 0,
+                    wins: 0,
+                    guesses: 0,
+                    guess_hits: 0,
+                })
+                .collect(),
+            failures: Vec::new(),
+        };
+
+        for record in records {
+            let failure = match &record.result {
+                GameResult::Finished { winner } => {
+                    report.finished += 1;
+                    report.game_matches.push(record.matches);
+                    report.game_tricks.push(record.tricks);
+
+                    match winner {
+                        Some(entry) => report.entries[*entry].wins += 1,
+                        None => report.draws += 1,
+                    }
+
+                    for (entry, entry_report) in report.entries.iter_mut().enumerate() {
+                        entry_report.games += 1;
+                        entry_report.guesses += record.guesses[entry];
+                        entry_report.guess_hits += record.guess_hits[entry];
+                    }
+
+                    None
+                }
+                GameResult::Failed { message } => {
+                    report.failed += 1;
+                    Some(("failed", message.clone()))
+                }
+                GameResult::Panicked { message } => {
+                    report.panicked += 1;
+                    Some(("panicked", message.clone()))
+                }
+                GameResult::Stalled => {
+                    report.stalled += 1;
+                    Some((
+                        "stalled",
+                        format!("No result after {} steps", options.max_steps),
+                    ))
+                }
+            };
+
+            if let Some((kind, message)) = failure
+                && report.failures.len() < MAX_LISTED_FAILURES
+            {
+                report.failures.push(FailureReport {
+                    seed: record.seed,
+                    kind,
+                    message,
+                });
+            }
+        }
+
+        report
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.render_text(),
+            OutputFormat::Csv => self.render_csv(),
+            OutputFormat::Json => self.render_json(),
+        }
+    }
+
+    fn render_text(&self) -> String {
+        let (average_matches, matches_margin) = mean_with_margin(&self.game_matches);
+        let (average_tricks, tricks_margin) = mean_with_margin(&self.game_tricks);
+
+        let mut text = format!(
+            "Games: {} (finished {}, draws {}, panicked {}, failed {}, stalled {})\n",
+            self.games, self.finished, self.draws, self.panicked, self.failed, self.stalled
+        );
+        text.push_str(&format!(
+            "Average length: {:.2} ± {:.2} matches, {:.2} ± {:.2} tricks\n\n",
+            average_matches, matches_margin, average_tricks, tricks_margin
+        ));
+        text.push_str(&format!(
+            "{:<6} {:<8} {:>6} {:>9} {:>18} {:>9} {:>18}\n",
+            "Entry", "Bot", "Games", "Win rate", "95% CI", "Accuracy", "95% CI"
+        ));
+
+        for (entry, entry_report) in self.entries.iter().enumerate() {
+            let win_rate = ratio(entry_report.wins, entry_report.games);
+            let (win_low, win_high) = wilson_interval(entry_report.wins, entry_report.games);
+            let accuracy = ratio(entry_report.guess_hits, entry_report.guesses);
+            let (accuracy_low, accuracy_high) =
+                wilson_interval(entry_report.guess_hits, entry_report.guesses);
+
+            text.push_str(&format!(
+                "{:<6} {:<8} {:>6} {:>8.1}% {:>18} {:>8.1}% {:>18}\n",
+                entry + 1,
+                entry_report.name,
+                entry_report.games,
+                win_rate * 100.0,
+                format!("[{:.1}%, {:.1}%]", win_low * 100.0, win_high * 100.0),
+                accuracy * 100.0,
+                format!(
+                    "[{:.1}%, {:.1}%]",
+                    accuracy_low * 100.0,
+                    accuracy_high * 100.0
+                ),
+            ));
+        }
+
+        if !self.failures.is_empty() {
+            text.push_str("\nFailures:\n");
+            for failure in &self.failures {
+                text.push_str(&format!(
+                    "  seed {}: {} ({})\n",
+                    failure.seed, failure.kind, failure.message
+                ));
+            }
+        }
+
+        text
+    }
+
+    fn render_csv(&self) -> String {
+        let (average_matches, _) = mean_with_margin(&self.game_matches);
+        let (average_tricks, _) = mean_with_margin(&self.game_tricks);
+
+        let mut csv = String::from(
+            "entry,bot,games,wins,win_rate,win_ci_low,win_ci_high,\
+guesses,guess_hits,guess_accuracy,guess_ci_low,guess_ci_high,average_matches,average_tricks\n",
+        );
+
+        for (entry, entry_report) in self.entries.iter().enumerate() {
+            let (win_low, win_high) = wilson_interval(entry_report.wins, entry_report.games);
+            let (accuracy_low, accuracy_high) =
+                wilson_interval(entry_report.guess_hits, entry_report.guesses);
+
+            csv.push_str(&format!(
+                "{},{},{},{},{:.4},{:.4},{:.4},{},{},{:.4},{:.4},{:.4},{:.4},{:.4}\n",
+                entry + 1,
+                entry_report.name,
+                entry_report.games,
+                entry_report.wins,
+                ratio(entry_report.wins, entry_report.games),
+                win_low,
+                win_high,
+                entry_report.guesses,
+                entry_report.guess_hits,
+                ratio(entry_report.guess_hits, entry_report.guesses),
+                accuracy_low,
+                accuracy_high,
+                average_matches,
+                average_tricks,
+            ));
+        }
+
+        csv
+    }
+
+    fn render_json(&self) -> String {
+        let (average_matches, matches_margin) = mean_with_margin(&self.game_matches);
+        let (average_tricks, tricks_margin) = mean_with_margin(&self.game_tricks);
+
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(entry, entry_report)| {
+                let (win_low, win_high) = wilson_interval(entry_report.wins, entry_report.games);
+                let (accuracy_low, accuracy_high) =
+                    wilson_interval(entry_report.guess_hits, entry_report.guesses);
+
+                format!(
+                    "{{\"entry\":{},\"bot\":\"{}\",\"games\":{},\"wins\":{},\"win_rate\":{:.4},\
+\"win_ci\":[{:.4},{:.4}],\"guesses\":{},\"guess_hits\":{},\"guess_accuracy\":{:.4},\
+\"guess_ci\":[{:.4},{:.4}]}}",
+                    entry + 1,
+                    entry_report.name,
+                    entry_report.games,
+                    entry_report.wins,
+                    ratio(entry_report.wins, entry_report.games),
+                    win_low,
+                    win_high,
+                    entry_report.guesses,
+                    entry_report.guess_hits,
+                    ratio(entry_report.guess_hits, entry_report.guesses),
+                    accuracy_low,
+                    accuracy_high,
+                )
+            })
+            .collect();
+
+        let failures: Vec<String> = self
+            .failures
+            .iter()
+            .map(|failure| {
+                format!(
+                    "{{\"seed\":{},\"kind\":\"{}\",\"message\":\"{}\"}}",
+                    failure.seed,
+                    failure.kind,
+                    escape_json(&failure.message)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"games\":{},\"finished\":{},\"draws\":{},\"panicked\":{},\"failed\":{},\
+\"stalled\":{},\"average_matches\":{:.4},\"average_matches_margin\":{:.4},\
+\"average_tricks\":{:.4},\"average_tricks_margin\":{:.4},\"entries\":[{}],\"failures\":[{}]}}",
+            self.games,
+            self.finished,
+            self.draws,
+            self.panicked,
+            self.failed,
+            self.stalled,
+            average_matches,
+            matches_margin,
+            average_tricks,
+            tricks_margin,
+            entries.join(","),
+            failures.join(","),
+        )
+    }
+}
+
+fn ratio(successes: usize, trials: usize) -> f64 {
+    if trials == 0 {
+        return 0.0;
+    }
+
+    successes as f64 / trials as f64
+}
+
+// Wilson score interval, which stays sensible for rates close to 0% or 100%
+fn wilson_interval(successes: usize, trials: usize) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 0.0);
+    }
+
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let margin = Z_95 * ((p * (1.0 - p) + z2 / (4.0 * n)) / n).sqrt() / denominator;
+
+    (center - margin, center + margin)
+}
+
+fn mean_with_margin(values: &[usize]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<usize>() as f64 / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+
+    let variance = values
+        .iter()
+        .map(|value| (*value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+
+    (mean, Z_95 * (variance / n).sqrt())
+}
+
+fn escape_json(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|c| match c {
+            '"' => vec!['\\', '"'],
+            '\\' => vec!['\\', '\\'],
+            '\n' => vec!['\\', 'n'],
+            c if c.is_control() => format!("\\u{:04x}", c as u32).chars().collect(),
+            c => vec![c],
+        })
+        .collect()
+}
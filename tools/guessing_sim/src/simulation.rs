@@ -0,0 +1,173 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use card_game_logic::{
+    bot::create_seeded_bot,
+    game_logic::{
+        GameLogic,
+        common::{CardPlayedResult, GameOutcome},
+        local::LocalGameLogic,
+    },
+};
+
+use crate::options::SimOptions;
+
+pub enum GameResult {
+    Finished { winner: Option<usize> },
+    Failed { message: String },
+    Panicked { message: String },
+    Stalled,
+}
+
+pub struct GameRecord {
+    pub seed: u64,
+    pub result: GameResult,
+    pub matches: usize,
+    pub tricks: usize,
+    // Indexed by bot entry, not by seat, since seats rotate between games
+    pub guesses: Vec<usize>,
+    pub guess_hits: Vec<usize>,
+}
+
+pub fn run_games(options: &SimOptions) -> Vec<GameRecord> {
+    (0..options.games)
+        .map(|game_index| run_game(options, game_index))
+        .collect()
+}
+
+fn run_game(options: &SimOptions, game_index: usize) -> GameRecord {
+    let seed = options.seed.wrapping_add(game_index as u64);
+    let entry_count = options.bots.len();
+    let mut record = GameRecord {
+        seed,
+        result: GameResult::Stalled,
+        matches: 0,
+        tricks: 0,
+        guesses: vec![0; entry_count],
+        guess_hits: vec![0; entry_count],
+    };
+
+    // Rotate the seats so no bot always gets the first guess
+    let seats: Vec<usize> = (0..entry_count)
+        .map(|seat| (seat + game_index) % entry_count)
+        .collect();
+
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        play_game(options, seed, &seats, &mut record)
+    }));
+
+    record.result = match outcome {
+        Ok(result) => result,
+        Err(payload) => GameResult::Panicked {
+            message: panic_message(payload.as_ref()),
+        },
+    };
+
+    record
+}
+
+fn play_game(
+    options: &SimOptions,
+    seed: u64,
+    seats: &[usize],
+    record: &mut GameRecord,
+) -> GameResult {
+    let player_count = seats.len();
+    let mut game_logic = LocalGameLogic::default();
+    game_logic.init(player_count, options.rules.clone());
+    game_logic.set_seed(seed);
+
+    let mut bots: Vec<_> = seats
+        .iter()
+        .enumerate()
+        .map(|(seat, entry)| {
+            create_seeded_bot(
+                options.bots[*entry],
+                seed.wrapping_mul(31).wrapping_add(seat as u64),
+            )
+        })
+        .collect();
+
+    let mut result = match game_logic.start_match(options.initial_card_count) {
+        Ok(result) => result,
+        Err(err) => {
+            return GameResult::Failed {
+                message: err.to_string(),
+            };
+        }
+    };
+    let mut lives = current_lives(&game_logic);
+    record.matches = 1;
+
+    for _ in 0..options.max_steps {
+        if result == CardPlayedResult::GameOver {
+            return match game_logic.get_outcome() {
+                Some(GameOutcome::Winner(seat)) => GameResult::Finished {
+                    winner: Some(seats[seat]),
+                },
+                Some(GameOutcome::Draw(_)) => GameResult::Finished { winner: None },
+                Some(GameOutcome::Abandoned) | None => GameResult::Failed {
+                    message: "Game ended without an outcome".to_string(),
+                },
+            };
+        }
+
+        let player_id = game_logic.get_player_turn();
+        let view = game_logic.get_player_view(player_id);
+        if game_logic.get_guessing_round() {
+            let guess = bots[player_id].choose_guess(&view);
+            if let Err(message) = game_logic.set_guess(player_id, guess) {
+                return GameResult::Failed { message };
+            }
+
+            continue;
+        }
+
+        let card = bots[player_id].choose_card(&view);
+        result = match game_logic.play_card(player_id, &card) {
+            Ok(result) => result,
+            Err(message) => return GameResult::Failed { message },
+        };
+
+        match result {
+            CardPlayedResult::NextTurn => record.tricks += 1,
+            CardPlayedResult::NextMatch | CardPlayedResult::GameOver => {
+                record.tricks += 1;
+
+                // A player only keeps their lives when the guess was right
+                let new_lives = current_lives(&game_logic);
+                for seat in 0..player_count {
+                    if lives[seat] > 0 {
+                        record.guesses[seats[seat]] += 1;
+                        if lives[seat] == new_lives[seat] {
+                            record.guess_hits[seats[seat]] += 1;
+                        }
+                    }
+                }
+
+                lives = new_lives;
+                if result == CardPlayedResult::NextMatch {
+                    record.matches += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    GameResult::Stalled
+}
+
+fn current_lives(game_logic: &LocalGameLogic) -> Vec<usize> {
+    (0..game_logic.get_player_count())
+        .map(|player_id| game_logic.get_player_lives(player_id))
+        .collect()
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic".to_string()
+    }
+}